@@ -1,25 +1,86 @@
-use crate::data::{SkillSummary, ViewKind};
+use crate::app::state::TabId;
+use crate::data::{RepoReference, SkillDetail, SkillKey, SkillSummary};
+use crate::error::AppError;
+use crate::install::{InstallTarget, Removal};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::time::SystemTime;
 
 #[derive(Debug, Clone)]
 pub enum Event {
     Key(KeyEvent),
     ViewLoaded {
-        view: ViewKind,
+        tab: TabId,
         skills: Vec<SkillSummary>,
+        fetched_at: SystemTime,
+        from_cache: bool,
     },
     DetailLoaded {
-        key: String,
-        markdown: String,
+        registry: usize,
+        key: SkillKey,
+        detail: Box<SkillDetail>,
     },
-    Error(String),
+    Error {
+        request: Request,
+        message: String,
+        hint: Option<&'static str>,
+    },
+    /// Something looked off but the operation still succeeded.
+    Warning(String),
+    /// A native install or an uninstall finished; the text reports what
+    /// happened.
+    InstallFinished(String),
+    /// The installed folders of `name`, ready to confirm removing.
+    UninstallPlanned {
+        name: String,
+        removals: Vec<Removal>,
+    },
+}
+
+/// The background request an [`Event::Error`] answers, so only the screen
+/// waiting on it stops loading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Request {
+    View(TabId),
+    Detail,
+    /// Work no tab or detail page is waiting on, such as planning an uninstall.
+    Task,
+}
+
+impl Event {
+    pub fn error(request: Request, error: &AppError) -> Self {
+        Event::Error {
+            request,
+            message: error.to_string(),
+            hint: error.hint(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum Action {
-    FetchView(ViewKind),
-    FetchDetail { source: String, skill_id: String },
-    InstallInteractive(SkillSummary),
+    FetchView(TabId),
+    FetchDetail {
+        registry: usize,
+        key: SkillKey,
+        git_ref: Option<String>,
+    },
+    /// Install `skill` natively into `target`, or with the registry's
+    /// install command when there is none.
+    Install {
+        registry: usize,
+        skill: SkillSummary,
+        target: Option<InstallTarget>,
+    },
+    BrowseRepo(RepoReference),
+    /// Find the installed copies of `key` and what removing them deletes.
+    PlanUninstall {
+        name: String,
+        key: SkillKey,
+    },
+    /// Move the confirmed folders to the trash.
+    Uninstall(Vec<Removal>),
+    /// Reinstall every skill the last update check found outdated.
+    UpdateAll,
 }
 
 pub fn key_to_action(key: KeyEvent) -> Option<KeyAction> {
@@ -31,9 +92,7 @@ pub fn key_to_action(key: KeyEvent) -> Option<KeyAction> {
 
         KeyCode::Tab => Some(KeyAction::NextTab),
         KeyCode::BackTab => Some(KeyAction::PrevTab),
-        KeyCode::Char('1') => Some(KeyAction::SelectTab(0)),
-        KeyCode::Char('2') => Some(KeyAction::SelectTab(1)),
-        KeyCode::Char('3') => Some(KeyAction::SelectTab(2)),
+        KeyCode::Char(c @ '1'..='9') => Some(KeyAction::SelectTab(c as usize - '1' as usize)),
 
         KeyCode::Up | KeyCode::Char('k') => Some(KeyAction::Up),
         KeyCode::Down | KeyCode::Char('j') => Some(KeyAction::Down),
@@ -47,7 +106,12 @@ pub fn key_to_action(key: KeyEvent) -> Option<KeyAction> {
 
         KeyCode::Char('/') => Some(KeyAction::StartSearch),
         KeyCode::Char('i') => Some(KeyAction::Install),
+        KeyCode::Char('u') => Some(KeyAction::Uninstall),
+        KeyCode::Char('U') => Some(KeyAction::UpdateAll),
         KeyCode::Char('r') => Some(KeyAction::Refresh),
+        KeyCode::Char('m') => Some(KeyAction::ToggleRaw),
+        KeyCode::Char('b') => Some(KeyAction::BrowseRef),
+        KeyCode::Char('o') => Some(KeyAction::OpenRepo),
         KeyCode::Char('?') => Some(KeyAction::Help),
 
         _ => None,
@@ -70,6 +134,11 @@ pub enum KeyAction {
     Back,
     StartSearch,
     Install,
+    Uninstall,
+    UpdateAll,
     Refresh,
+    ToggleRaw,
+    BrowseRef,
+    OpenRepo,
     Help,
 }
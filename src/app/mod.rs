@@ -1,21 +1,24 @@
 pub mod events;
 pub mod state;
 
-use crate::data::ViewKind;
+use crate::data::installed::{INSTALLED_VIEW, UPDATES_VIEW};
+use crate::data::{RepoReference, SkillRegistry, SkillSummary};
+use crate::install::Installer;
 use crossterm::event::KeyCode;
-use events::{key_to_action, Action, Event, KeyAction};
+use events::{key_to_action, Action, Event, KeyAction, Request};
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
-use state::{AppState, Mode};
+use state::{AppState, Mode, PendingInstall, PendingUninstall, TabId, ViewState};
+use std::sync::Arc;
 
 pub struct App {
     pub state: AppState,
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(registries: Vec<Arc<dyn SkillRegistry>>) -> Self {
         Self {
-            state: AppState::new(),
+            state: AppState::new(registries),
         }
     }
 
@@ -39,32 +42,101 @@ impl App {
                         _ => {}
                     }
                 }
+                let input = match self.state.mode {
+                    Mode::RefInput => Some(&mut self.state.ref_input),
+                    Mode::RepoInput => Some(&mut self.state.repo_input),
+                    _ => None,
+                };
+                if let Some(input) = input {
+                    match key.code {
+                        KeyCode::Char(c) => {
+                            input.push(c);
+                            return actions;
+                        }
+                        KeyCode::Backspace => {
+                            input.pop();
+                            return actions;
+                        }
+                        _ => {}
+                    }
+                }
                 if let Some(action) = key_to_action(key) {
                     actions.extend(self.handle_key_action(action));
                 }
             }
-            Event::ViewLoaded { view, skills } => {
-                if let Some(view_state) = self.state.views.get_mut(&view) {
+            Event::ViewLoaded {
+                tab,
+                skills,
+                fetched_at,
+                from_cache,
+            } => {
+                if let Some(view_state) = self.state.views.get_mut(&tab) {
                     view_state.skills = skills;
-                    view_state.loading = false;
+                    view_state.fetched_at = Some(fetched_at);
+                    view_state.from_cache = from_cache;
+                    if !from_cache {
+                        view_state.loading = false;
+                    }
                     view_state.error = None;
+                    view_state.filtered_indices.clear();
                     if view_state.list_state.selected().is_none() && !view_state.skills.is_empty() {
                         view_state.list_state.select(Some(0));
                     }
                 }
+                // Keep an active search applied when a refresh replaces the list.
+                if tab == self.state.current_tab() && !self.state.search_query.is_empty() {
+                    self.apply_filter();
+                }
             }
-            Event::DetailLoaded { key, markdown } => {
+            Event::DetailLoaded {
+                registry,
+                key,
+                detail,
+            } => {
                 self.state.detail_loading = false;
-                self.state
-                    .detail_cache
-                    .insert(key, crate::data::SkillDetail { markdown });
+                self.state.detail_cache.insert((registry, key), *detail);
             }
-            Event::Error(msg) => {
-                self.state.status_message = Some(format!("Error: {}", msg));
-                self.state.detail_loading = false;
-                if let Some(view_state) = self.state.views.get_mut(&self.state.current_view) {
-                    view_state.loading = false;
-                    view_state.error = Some(msg);
+            Event::Error {
+                request,
+                message,
+                hint,
+            } => {
+                self.state.status_message = Some(match hint {
+                    Some(hint) => format!("Error: {} ({})", message, hint),
+                    None => format!("Error: {}", message),
+                });
+                match request {
+                    Request::View(tab) => {
+                        if let Some(view_state) = self.state.views.get_mut(&tab) {
+                            view_state.loading = false;
+                            view_state.error = Some(message);
+                        }
+                    }
+                    Request::Detail => self.state.detail_loading = false,
+                    Request::Task => {}
+                }
+            }
+            Event::Warning(message) => {
+                self.state.status_message = Some(format!("Warning: {}", message));
+            }
+            Event::InstallFinished(output) => {
+                self.state.install_output = output;
+                // Rescan, so the change shows in the installed markers.
+                for tab in self.state.local_tabs() {
+                    actions.push(Action::FetchView(tab));
+                }
+            }
+            Event::UninstallPlanned { name, removals } => {
+                if removals.is_empty() {
+                    // Copies matched by name alone are removed from the
+                    // Installed tab, where their folder is listed.
+                    self.state.status_message = Some(format!(
+                        "{} is not installed from this source; uninstall it from the Installed tab",
+                        name
+                    ));
+                } else {
+                    self.state.pending_uninstall = Some(PendingUninstall { name, removals });
+                    self.state.mode = Mode::ConfirmUninstall;
                 }
             }
         }
@@ -79,24 +151,18 @@ impl App {
             Mode::List => match action {
                 KeyAction::Quit => self.state.should_quit = true,
                 KeyAction::NextTab => {
-                    self.state.current_view = self.state.current_view.next();
-                    if self.state.current_view_state().skills.is_empty() {
-                        actions.push(Action::FetchView(self.state.current_view));
-                    }
+                    self.state.current_tab = (self.state.current_tab + 1) % self.state.tabs.len();
+                    actions.extend(self.refresh_if_stale());
                 }
                 KeyAction::PrevTab => {
-                    self.state.current_view = self.state.current_view.prev();
-                    if self.state.current_view_state().skills.is_empty() {
-                        actions.push(Action::FetchView(self.state.current_view));
-                    }
+                    let len = self.state.tabs.len();
+                    self.state.current_tab = (self.state.current_tab + len - 1) % len;
+                    actions.extend(self.refresh_if_stale());
                 }
                 KeyAction::SelectTab(idx) => {
-                    let views = ViewKind::all();
-                    if let Some(&view) = views.get(idx) {
-                        self.state.current_view = view;
-                        if self.state.current_view_state().skills.is_empty() {
-                            actions.push(Action::FetchView(view));
-                        }
+                    if idx < self.state.tabs.len() {
+                        self.state.current_tab = idx;
+                        actions.extend(self.refresh_if_stale());
                     }
                 }
                 KeyAction::Up => self.move_selection(-1),
@@ -108,11 +174,18 @@ impl App {
                 KeyAction::Select => {
                     if let Some(skill) = self.state.selected_skill().cloned() {
                         self.state.mode = Mode::Detail;
-                        if !self.state.detail_cache.contains_key(&skill.skill_id) {
+                        let registry = self.state.current_tab().registry;
+                        let key = skill.key();
+                        if !self
+                            .state
+                            .detail_cache
+                            .contains_key(&(registry, key.clone()))
+                        {
                             self.state.detail_loading = true;
                             actions.push(Action::FetchDetail {
-                                source: skill.source.clone(),
-                                skill_id: skill.skill_id.clone(),
+                                registry,
+                                key,
+                                git_ref: None,
                             });
                         }
                     }
@@ -123,20 +196,54 @@ impl App {
                 }
                 KeyAction::Install => {
                     if let Some(skill) = self.state.selected_skill().cloned() {
-                        actions.push(Action::InstallInteractive(skill));
+                        actions.extend(self.start_install(skill));
                     }
                 }
-                KeyAction::Refresh => {
-                    let view = self.state.current_view;
-                    if let Some(view_state) = self.state.views.get_mut(&view) {
-                        view_state.loading = true;
+                KeyAction::Uninstall => {
+                    if let Some(skill) = self.state.selected_skill() {
+                        let key = skill.key();
+                        let listed = self.state.current_tab().view == INSTALLED_VIEW;
+                        if listed || self.state.is_installed(skill) {
+                            actions.push(Action::PlanUninstall {
+                                name: skill.name.clone(),
+                                key,
+                            });
+                        } else {
+                            self.state.status_message =
+                                Some(format!("{} is not installed", skill.name));
+                        }
                     }
-                    actions.push(Action::FetchView(view));
+                }
+                KeyAction::UpdateAll => {
+                    let view = self.state.current_view_state();
+                    let (loading, outdated) = (view.loading, view.skills.len());
+                    if self.state.current_tab().view != UPDATES_VIEW {
+                        self.state.status_message =
+                            Some("Open the Updates tab to update skills".to_string());
+                    } else if loading {
+                        self.state.status_message = Some("Still checking for updates".to_string());
+                    } else if outdated == 0 {
+                        self.state.status_message = Some("Everything is up to date".to_string());
+                    } else {
+                        self.state.mode = Mode::Installing;
+                        self.state.install_command = format!("update {} skill(s)", outdated);
+                        self.state.install_output.clear();
+                        actions.push(Action::UpdateAll);
+                    }
+                }
+                KeyAction::Refresh => {
+                    let tab = self.state.current_tab();
+                    self.state.current_view_state_mut().loading = true;
+                    actions.push(Action::FetchView(tab));
                 }
                 KeyAction::Help => {
                     self.state.mode = Mode::Help;
                 }
-                KeyAction::Back => {}
+                KeyAction::OpenRepo => {
+                    self.state.mode = Mode::RepoInput;
+                    self.state.repo_input.clear();
+                }
+                KeyAction::Back | KeyAction::ToggleRaw | KeyAction::BrowseRef => {}
             },
             Mode::Detail => match action {
                 KeyAction::Quit => self.state.should_quit = true,
@@ -159,6 +266,57 @@ impl App {
                 KeyAction::Top => {
                     self.state.detail_scroll = 0;
                 }
+                KeyAction::ToggleRaw => {
+                    self.state.detail_raw = !self.state.detail_raw;
+                    self.state.detail_scroll = 0;
+                }
+                KeyAction::BrowseRef => {
+                    self.state.mode = Mode::RefInput;
+                    self.state.ref_input = self
+                        .state
+                        .selected_detail()
+                        .and_then(|d| d.git_ref.clone())
+                        .unwrap_or_default();
+                }
+                _ => {}
+            },
+            Mode::RefInput => match action {
+                KeyAction::Back => {
+                    self.state.mode = Mode::Detail;
+                    self.state.ref_input.clear();
+                }
+                KeyAction::Select => {
+                    self.state.mode = Mode::Detail;
+                    if let Some(skill) = self.state.selected_skill().cloned() {
+                        let git_ref = std::mem::take(&mut self.state.ref_input);
+                        self.state.detail_loading = true;
+                        self.state.detail_scroll = 0;
+                        actions.push(Action::FetchDetail {
+                            registry: self.state.current_tab().registry,
+                            key: skill.key(),
+                            git_ref: Some(git_ref).filter(|r| !r.trim().is_empty()),
+                        });
+                    }
+                }
+                _ => {}
+            },
+            Mode::RepoInput => match action {
+                KeyAction::Back => {
+                    self.state.mode = Mode::List;
+                    self.state.repo_input.clear();
+                }
+                KeyAction::Select => {
+                    self.state.mode = Mode::List;
+                    let input = std::mem::take(&mut self.state.repo_input);
+                    match RepoReference::parse(&input) {
+                        Some(repo) => actions.push(Action::BrowseRepo(repo)),
+                        None if input.trim().is_empty() => {}
+                        None => {
+                            self.state.status_message =
+                                Some(format!("Not a repository: {}", input.trim()));
+                        }
+                    }
+                }
                 _ => {}
             },
             Mode::Help => match action {
@@ -179,7 +337,59 @@ impl App {
                 }
                 _ => {}
             },
-            Mode::Installing => match action {
+            Mode::PickTarget => match action {
+                KeyAction::Quit => self.state.should_quit = true,
+                KeyAction::Back => {
+                    self.state.mode = Mode::List;
+                    self.state.pending_install = None;
+                }
+                KeyAction::Up | KeyAction::Down => {
+                    let len = self.state.install_targets().len();
+                    if let Some(pending) = &mut self.state.pending_install {
+                        pending.selected = match action {
+                            KeyAction::Up => pending.selected.saturating_sub(1),
+                            _ => (pending.selected + 1).min(len.saturating_sub(1)),
+                        };
+                    }
+                }
+                KeyAction::Select => {
+                    if let Some(pending) = self.state.pending_install.take() {
+                        if let Some(target) =
+                            self.state.install_targets().get(pending.selected).cloned()
+                        {
+                            self.state.preferred_target = pending.selected;
+                            self.state.mode = Mode::Installing;
+                            self.state.install_command =
+                                format!("{} into {}", pending.skill.skill_id, target.dir.display());
+                            self.state.install_output.clear();
+                            actions.push(Action::Install {
+                                registry: pending.registry,
+                                skill: pending.skill,
+                                target: Some(target),
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Mode::ConfirmUninstall => match action {
+                KeyAction::Quit => self.state.should_quit = true,
+                KeyAction::Back => {
+                    self.state.mode = Mode::List;
+                    self.state.pending_uninstall = None;
+                }
+                KeyAction::Select => {
+                    if let Some(pending) = self.state.pending_uninstall.take() {
+                        self.state.mode = Mode::Uninstalling;
+                        self.state.install_command =
+                            format!("{} from {} folder(s)", pending.name, pending.removals.len());
+                        self.state.install_output.clear();
+                        actions.push(Action::Uninstall(pending.removals));
+                    }
+                }
+                _ => {}
+            },
+            Mode::Installing | Mode::Uninstalling => match action {
                 KeyAction::Quit => self.state.should_quit = true,
                 KeyAction::Back | KeyAction::Select => {
                     self.state.mode = Mode::List;
@@ -192,6 +402,79 @@ impl App {
         actions
     }
 
+    /// Install `skill` from the current registry: through its install command
+    /// with the npx installer, otherwise after picking a target.
+    fn start_install(&mut self, skill: SkillSummary) -> Option<Action> {
+        let registry = self.state.current_tab().registry;
+        if matches!(self.state.installer, Installer::Npx) {
+            return Some(Action::Install {
+                registry,
+                skill,
+                target: None,
+            });
+        }
+        if !self.state.registries[registry].installable(&skill) {
+            self.state.status_message = Some("This skill cannot be installed from here".into());
+            return None;
+        }
+        if self.state.install_targets().is_empty() {
+            self.state.status_message = Some("No install targets configured".into());
+            return None;
+        }
+        let selected = self
+            .state
+            .preferred_target
+            .min(self.state.install_targets().len() - 1);
+        self.state.pending_install = Some(PendingInstall {
+            registry,
+            skill,
+            selected,
+        });
+        self.state.mode = Mode::PickTarget;
+        None
+    }
+
+    /// Add `registry`'s tabs and switch to its first one, or just switch if a
+    /// registry with the same id is already open.
+    pub fn open_registry(&mut self, registry: Arc<dyn SkillRegistry>) -> Option<Action> {
+        let state = &mut self.state;
+        let index = match state
+            .registries
+            .iter()
+            .position(|r| r.id() == registry.id())
+        {
+            Some(index) => index,
+            None => {
+                let index = state.registries.len();
+                for view in registry.views() {
+                    let tab = TabId {
+                        registry: index,
+                        view,
+                    };
+                    state.tabs.push(tab);
+                    state.views.insert(tab, ViewState::default());
+                }
+                state.registries.push(registry);
+                index
+            }
+        };
+        state.current_tab = state.tabs.iter().position(|t| t.registry == index)?;
+        self.refresh_if_stale()
+    }
+
+    /// Fetch the current view if it is empty or its cached contents are
+    /// older than the configured TTL.
+    pub fn refresh_if_stale(&mut self) -> Option<Action> {
+        let ttl = self.state.cache_ttl;
+        let tab = self.state.current_tab();
+        let view_state = self.state.current_view_state_mut();
+        if !view_state.needs_refresh(ttl) {
+            return None;
+        }
+        view_state.loading = true;
+        Some(Action::FetchView(tab))
+    }
+
     fn move_selection(&mut self, delta: i32) {
         let view_state = self.state.current_view_state_mut();
         let len = view_state.len();
@@ -247,7 +530,7 @@ impl App {
             })
             .collect();
 
-        scored.sort_by(|a, b| b.1.cmp(&a.1));
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
         view_state.filtered_indices = scored.into_iter().map(|(idx, _)| idx).collect();
         view_state
             .list_state
@@ -258,9 +541,3 @@ impl App {
             });
     }
 }
-
-impl Default for App {
-    fn default() -> Self {
-        Self::new()
-    }
-}
@@ -1,6 +1,10 @@
-use crate::data::{SkillDetail, SkillSummary, ViewKind};
+use crate::data::installed::INSTALLED_VIEW;
+use crate::data::{RateLimit, SkillDetail, SkillKey, SkillRegistry, SkillSummary, ViewKind};
+use crate::install::{self, InstallTarget, Installer, Removal};
 use ratatui::widgets::ListState;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Mode {
@@ -10,6 +14,19 @@ pub enum Mode {
     Search,
     Help,
     Installing,
+    PickTarget,
+    ConfirmUninstall,
+    Uninstalling,
+    RefInput,
+    RepoInput,
+}
+
+/// A tab: one view of one registry. `registry` indexes
+/// [`AppState::registries`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TabId {
+    pub registry: usize,
+    pub view: ViewKind,
 }
 
 #[derive(Debug, Default)]
@@ -19,6 +36,9 @@ pub struct ViewState {
     pub list_state: ListState,
     pub loading: bool,
     pub error: Option<String>,
+    pub fetched_at: Option<SystemTime>,
+    /// Whether the skills came from the on-disk cache rather than a fetch.
+    pub from_cache: bool,
 }
 
 impl ViewState {
@@ -51,44 +71,210 @@ impl ViewState {
             self.filtered_indices.len()
         }
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the view should be fetched: it is empty, or it is showing
+    /// cached contents older than `ttl`.
+    pub fn needs_refresh(&self, ttl: Duration) -> bool {
+        if self.loading {
+            return false;
+        }
+        if self.skills.is_empty() {
+            return true;
+        }
+        self.from_cache && self.age().is_none_or(|age| age >= ttl)
+    }
+
+    pub fn age(&self) -> Option<Duration> {
+        self.fetched_at.and_then(|t| t.elapsed().ok())
+    }
 }
 
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct AppState {
     pub mode: Mode,
-    pub current_view: ViewKind,
-    pub views: HashMap<ViewKind, ViewState>,
+    pub registries: Vec<Arc<dyn SkillRegistry>>,
+    /// Every registry's views, grouped by registry, in tab order.
+    pub tabs: Vec<TabId>,
+    pub current_tab: usize,
+    pub views: HashMap<TabId, ViewState>,
     pub search_query: String,
-    pub detail_cache: HashMap<String, SkillDetail>,
+    pub detail_cache: HashMap<(usize, SkillKey), SkillDetail>,
     pub detail_loading: bool,
     pub detail_scroll: u16,
+    pub detail_raw: bool,
+    pub ref_input: String,
+    pub repo_input: String,
     pub status_message: Option<String>,
     pub should_quit: bool,
     pub install_output: String,
     pub install_command: String,
+    pub installer: Installer,
+    /// Install waiting for a target to be picked.
+    pub pending_install: Option<PendingInstall>,
+    /// Uninstall waiting for confirmation.
+    pub pending_uninstall: Option<PendingUninstall>,
+    /// Index of the target picked last, preselected in the picker.
+    pub preferred_target: usize,
+    pub cache_ttl: Duration,
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl AppState {
-    pub fn new() -> Self {
-        let mut views = HashMap::new();
-        for view in ViewKind::all() {
-            views.insert(view, ViewState::default());
-        }
+    pub fn new(registries: Vec<Arc<dyn SkillRegistry>>) -> Self {
+        let tabs: Vec<TabId> = registries
+            .iter()
+            .enumerate()
+            .flat_map(|(registry, r)| {
+                r.views()
+                    .into_iter()
+                    .map(move |view| TabId { registry, view })
+            })
+            .collect();
+        let views = tabs
+            .iter()
+            .map(|&tab| (tab, ViewState::default()))
+            .collect();
         Self {
+            registries,
+            tabs,
             views,
             ..Default::default()
         }
     }
 
+    pub fn current_tab(&self) -> TabId {
+        self.tabs[self.current_tab]
+    }
+
+    pub fn current_registry(&self) -> &Arc<dyn SkillRegistry> {
+        &self.registries[self.current_tab().registry]
+    }
+
+    /// Tab label, prefixed with the registry name when several are shown.
+    pub fn tab_label(&self, tab: TabId) -> String {
+        if self.registries.len() > 1 && !self.registries[tab.registry].is_local() {
+            format!(
+                "{} · {}",
+                self.registries[tab.registry].name(),
+                tab.view.label()
+            )
+        } else {
+            tab.view.label().to_string()
+        }
+    }
+
     pub fn current_view_state(&self) -> &ViewState {
-        self.views.get(&self.current_view).unwrap()
+        self.views.get(&self.current_tab()).unwrap()
     }
 
     pub fn current_view_state_mut(&mut self) -> &mut ViewState {
-        self.views.get_mut(&self.current_view).unwrap()
+        let tab = self.current_tab();
+        self.views.get_mut(&tab).unwrap()
+    }
+
+    /// The fetched document of the selected skill, if any.
+    pub fn selected_detail(&self) -> Option<&SkillDetail> {
+        let skill = self.selected_skill()?;
+        let registry = self.current_tab().registry;
+        self.detail_cache.get(&(registry, skill.key()))
+    }
+
+    /// How the selected skill is installed: the command line, or where a
+    /// native install puts it.
+    pub fn selected_install_command(&self) -> Option<String> {
+        let skill = self.selected_skill()?;
+        let registry = self.current_registry();
+        match &self.installer {
+            Installer::Npx => Some(registry.install_command(skill)?.join(" ")),
+            Installer::Native { .. } => {
+                if !registry.installable(skill) {
+                    return None;
+                }
+                let folder = install::install_folder(&skill.skill_id).ok()?;
+                let target = self.preferred_target()?;
+                Some(format!("into {}", target.dir.join(folder).display()))
+            }
+        }
+    }
+
+    /// Web page of the selected skill's repository.
+    pub fn selected_source_url(&self) -> Option<String> {
+        let skill = self.selected_skill()?;
+        self.current_registry().source_url(skill)
+    }
+
+    /// Registry-specific facts about the selected skill.
+    pub fn selected_detail_fields(&self) -> Vec<(&'static str, String)> {
+        match self.selected_skill() {
+            Some(skill) => self.current_registry().detail_fields(skill),
+            None => Vec::new(),
+        }
+    }
+
+    /// The registry describing this machine, when one is open.
+    pub fn local_registry(&self) -> Option<&Arc<dyn SkillRegistry>> {
+        self.registries.iter().find(|registry| registry.is_local())
+    }
+
+    /// Whether the last scan of the install targets found `skill`.
+    pub fn is_installed(&self, skill: &SkillSummary) -> bool {
+        self.local_registry()
+            .is_some_and(|registry| registry.has_installed(skill))
+    }
+
+    /// The tab listing installed skills, when one is open.
+    pub fn installed_tab(&self) -> Option<TabId> {
+        self.tabs
+            .iter()
+            .copied()
+            .find(|tab| tab.view == INSTALLED_VIEW)
+    }
+
+    /// The tabs describing installed skills that have been loaded, and so
+    /// go stale when skills are installed or removed.
+    pub fn local_tabs(&self) -> Vec<TabId> {
+        self.tabs
+            .iter()
+            .copied()
+            .filter(|tab| self.registries[tab.registry].is_local())
+            .filter(|tab| self.views.get(tab).is_some_and(|v| v.fetched_at.is_some()))
+            .collect()
     }
 
     pub fn selected_skill(&self) -> Option<&SkillSummary> {
         self.current_view_state().selected_skill()
     }
+
+    /// Targets native installs can write to; none with the npx installer.
+    pub fn install_targets(&self) -> &[InstallTarget] {
+        match &self.installer {
+            Installer::Native { targets } => targets,
+            Installer::Npx => &[],
+        }
+    }
+
+    pub fn preferred_target(&self) -> Option<&InstallTarget> {
+        let targets = self.install_targets();
+        targets.get(self.preferred_target).or(targets.first())
+    }
+}
+
+/// A skill about to be installed, once a target is picked.
+#[derive(Debug, Clone)]
+pub struct PendingInstall {
+    pub registry: usize,
+    pub skill: SkillSummary,
+    /// Index of the highlighted target.
+    pub selected: usize,
+}
+
+/// Installed folders about to be moved to the trash, once confirmed.
+#[derive(Debug, Clone)]
+pub struct PendingUninstall {
+    pub name: String,
+    pub removals: Vec<Removal>,
 }
@@ -0,0 +1,369 @@
+use crate::data::host::HostKind;
+use crate::data::installed::INSTALLED_ID;
+use crate::error::{AppError, Result};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// User configuration, read from `$XDG_CONFIG_HOME/skillsm/config.toml`.
+///
+/// Every section is optional; a missing file yields the defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub cache: CacheConfig,
+    pub github: GithubConfig,
+    pub network: NetworkConfig,
+    pub install: InstallConfig,
+    /// Catalogs to browse, one tab group each. Empty means skills.sh alone.
+    pub registries: Vec<RegistryConfig>,
+    /// Self-hosted git servers that skill sources may live on.
+    pub hosts: Vec<HostConfig>,
+}
+
+/// One `[[hosts]]` entry, declaring the API of a git server that cannot be
+/// recognised from its domain.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HostConfig {
+    /// Domain as it appears in sources, e.g. `git.example.com`.
+    pub domain: String,
+    pub kind: HostKind,
+    /// API base URL, when it is not at the kind's usual location.
+    pub api_url: Option<String>,
+    /// Access token sent with API requests to this host.
+    pub token: Option<String>,
+    /// Base of clone URLs, `https://<domain>` by default. A repository is
+    /// cloned from `<clone_url>/<path>`.
+    pub clone_url: Option<String>,
+}
+
+/// One `[[registries]]` entry.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegistryConfig {
+    /// Name shown in the tab bar; also names the registry's cache directory.
+    pub name: String,
+    #[serde(default)]
+    pub kind: RegistryKind,
+    /// Base URL, for registries served over HTTP.
+    pub url: Option<String>,
+    /// Directory to scan, for `local` registries.
+    pub path: Option<PathBuf>,
+    /// How skill documents are read from their repositories.
+    #[serde(default)]
+    pub fetch: FetchMode,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RegistryKind {
+    /// skills.sh, or a mirror serving the same pages.
+    #[default]
+    SkillsSh,
+    /// A directory tree of SKILL.md files.
+    Local,
+    /// A static `index.json`, given as `path` or a `file://` `url`.
+    Index,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FetchMode {
+    /// Through the host's API and raw file endpoints.
+    #[default]
+    Api,
+    /// Shallow-fetched with the system `git` and read from a local checkout.
+    Git,
+}
+
+impl RegistryConfig {
+    /// File-name safe identifier derived from the name.
+    pub fn id(&self) -> String {
+        self.name
+            .trim()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '.' | '_') {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect()
+    }
+}
+
+/// How the `i` key installs skills.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InstallConfig {
+    pub method: InstallMethod,
+    /// Agents to offer as install targets, in addition to the built-in ones.
+    /// An entry named like a built-in agent replaces it.
+    pub agents: Vec<AgentConfig>,
+}
+
+/// One `[[install.agents]]` entry: where an agent looks for skills.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentConfig {
+    pub name: String,
+    /// Skills directory shared by every project; `~` is the home directory.
+    pub global: Option<PathBuf>,
+    /// Skills directory relative to the project root.
+    pub project: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstallMethod {
+    /// skillsm downloads the skill's folder itself.
+    #[default]
+    Native,
+    /// The registry's install command, usually `npx skills add`.
+    Npx,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GithubConfig {
+    /// Personal access token used for GitHub API requests.
+    pub token: Option<String>,
+}
+
+impl GithubConfig {
+    /// The token from `GITHUB_TOKEN` or `GH_TOKEN`, falling back to the
+    /// config file.
+    pub fn token(&self) -> Option<String> {
+        ["GITHUB_TOKEN", "GH_TOKEN"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|token| !token.trim().is_empty())
+            .or_else(|| self.token.clone())
+    }
+}
+
+/// Endpoint and HTTP overrides, for running against a mirror or a stub
+/// server. Each setting can also be given as a `SKILLSM_*` environment
+/// variable, which takes precedence over the file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// Base URL of the skills.sh leaderboard (`SKILLSM_REGISTRY_URL`).
+    pub registry_url: Option<String>,
+    /// Base URL of the GitHub REST API (`SKILLSM_GITHUB_API_URL`).
+    pub github_api_url: Option<String>,
+    /// Base URL serving raw repository files (`SKILLSM_GITHUB_RAW_URL`).
+    pub github_raw_url: Option<String>,
+    /// Request timeout in seconds (`SKILLSM_TIMEOUT_SECS`).
+    pub timeout_secs: Option<u64>,
+    /// User-Agent header sent with every request (`SKILLSM_USER_AGENT`).
+    pub user_agent: Option<String>,
+    /// Proxy for every request (`SKILLSM_PROXY`).
+    pub proxy: Option<String>,
+    /// Extra PEM CA certificate to trust (`SKILLSM_CA_CERT`).
+    pub ca_cert: Option<PathBuf>,
+}
+
+impl NetworkConfig {
+    pub fn registry_url(&self) -> Option<String> {
+        env_or("SKILLSM_REGISTRY_URL", &self.registry_url)
+    }
+
+    pub fn github_api_url(&self) -> Option<String> {
+        env_or("SKILLSM_GITHUB_API_URL", &self.github_api_url)
+    }
+
+    pub fn github_raw_url(&self) -> Option<String> {
+        env_or("SKILLSM_GITHUB_RAW_URL", &self.github_raw_url)
+    }
+
+    pub fn timeout(&self) -> Result<Option<Duration>> {
+        let secs = match env_var("SKILLSM_TIMEOUT_SECS") {
+            Some(value) => Some(value.trim().parse().map_err(|_| {
+                AppError::Config(format!("SKILLSM_TIMEOUT_SECS: invalid number {:?}", value))
+            })?),
+            None => self.timeout_secs,
+        };
+        Ok(secs.map(Duration::from_secs))
+    }
+
+    pub fn user_agent(&self) -> Option<String> {
+        env_or("SKILLSM_USER_AGENT", &self.user_agent)
+    }
+
+    pub fn proxy(&self) -> Option<String> {
+        env_or("SKILLSM_PROXY", &self.proxy)
+    }
+
+    pub fn ca_cert(&self) -> Option<PathBuf> {
+        env_var("SKILLSM_CA_CERT")
+            .map(PathBuf::from)
+            .or_else(|| self.ca_cert.clone())
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+fn env_or(name: &str, fallback: &Option<String>) -> Option<String> {
+    env_var(name).or_else(|| fallback.clone())
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// How long a cached leaderboard is shown before it is refreshed.
+    pub ttl_minutes: u64,
+    /// Size limit of the SKILL.md document cache, in megabytes.
+    pub max_document_mb: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl_minutes: 60,
+            max_document_mb: 50,
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl_minutes * 60)
+    }
+
+    pub fn max_document_bytes(&self) -> u64 {
+        self.max_document_mb * 1024 * 1024
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        match config_dir() {
+            Some(dir) => Self::load_from(&dir.join("config.toml")),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &std::path::Path) -> Result<Self> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        let config: Self = toml::from_str(&contents)
+            .map_err(|e| AppError::Config(format!("{}: {}", path.display(), e)))?;
+        config
+            .validate()
+            .map_err(|e| AppError::Config(format!("{}: {}", path.display(), e)))?;
+        Ok(config)
+    }
+
+    /// The configured registries, or skills.sh when none are listed.
+    pub fn registries(&self) -> Vec<RegistryConfig> {
+        if !self.registries.is_empty() {
+            return self.registries.clone();
+        }
+        vec![RegistryConfig {
+            name: "skills.sh".to_string(),
+            kind: RegistryKind::SkillsSh,
+            url: None,
+            path: None,
+            fetch: FetchMode::Api,
+        }]
+    }
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        let mut ids = std::collections::HashSet::new();
+        for registry in &self.registries {
+            if registry.name.trim().is_empty() {
+                return Err("registry name must not be empty".to_string());
+            }
+            if registry.id() == INSTALLED_ID {
+                return Err(format!(
+                    "registry name {:?} is reserved for the Installed tab",
+                    registry.name
+                ));
+            }
+            if !ids.insert(registry.id()) {
+                return Err(format!("duplicate registry name {:?}", registry.name));
+            }
+            if registry.kind == RegistryKind::Local && registry.path.is_none() {
+                return Err(format!("local registry {:?} needs a path", registry.name));
+            }
+            if registry.kind == RegistryKind::Index
+                && registry.path.is_none()
+                && registry.url.is_none()
+            {
+                return Err(format!(
+                    "index registry {:?} needs a path or url",
+                    registry.name
+                ));
+            }
+            if registry.kind != RegistryKind::SkillsSh && registry.fetch == FetchMode::Git {
+                return Err(format!(
+                    "registry {:?}: fetch = \"git\" only applies to skills-sh registries",
+                    registry.name
+                ));
+            }
+        }
+        let mut agents = std::collections::HashSet::new();
+        for agent in &self.install.agents {
+            if agent.name.trim().is_empty() {
+                return Err("agent name must not be empty".to_string());
+            }
+            if !agents.insert(agent.name.trim()) {
+                return Err(format!("duplicate agent {:?}", agent.name));
+            }
+            if agent.global.is_none() && agent.project.is_none() {
+                return Err(format!(
+                    "agent {:?} needs a global or project directory",
+                    agent.name
+                ));
+            }
+            if agent.project.as_ref().is_some_and(|p| p.is_absolute()) {
+                return Err(format!(
+                    "agent {:?}: project directory must be relative",
+                    agent.name
+                ));
+            }
+        }
+        let mut domains = std::collections::HashSet::new();
+        for host in &self.hosts {
+            let domain = host.domain.trim().to_ascii_lowercase();
+            if domain.is_empty() || domain.contains('/') {
+                return Err(format!("invalid host domain {:?}", host.domain));
+            }
+            if !domains.insert(domain) {
+                return Err(format!("duplicate host {:?}", host.domain));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `$XDG_CONFIG_HOME/skillsm`, falling back to `~/.config/skillsm`.
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// `$XDG_DATA_HOME/skillsm`, falling back to `~/.local/share/skillsm`.
+pub fn data_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+/// `$XDG_CACHE_HOME/skillsm`, falling back to `~/.cache/skillsm`.
+pub fn cache_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CACHE_HOME", ".cache")
+}
+
+fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    let base = std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(fallback)))?;
+    Some(base.join("skillsm"))
+}
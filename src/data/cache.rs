@@ -0,0 +1,228 @@
+use crate::data::{SkillKey, SkillSummary, ViewKind};
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A leaderboard as it was last fetched from skills.sh.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedView {
+    /// Seconds since the Unix epoch.
+    pub fetched_at: u64,
+    pub skills: Vec<SkillSummary>,
+}
+
+impl CachedView {
+    pub fn fetched_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.fetched_at)
+    }
+}
+
+/// On-disk cache of leaderboard contents, one JSON file per view.
+#[derive(Debug, Clone)]
+pub struct ViewCache {
+    dir: PathBuf,
+}
+
+impl ViewCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, view: ViewKind) -> PathBuf {
+        self.dir.join(format!("{}.json", view.slug()))
+    }
+
+    /// Load a cached view; a missing or unreadable entry counts as a miss.
+    pub fn load(&self, view: ViewKind) -> Option<CachedView> {
+        let contents = std::fs::read(self.path(view)).ok()?;
+        serde_json::from_slice(&contents).ok()
+    }
+
+    pub fn store(
+        &self,
+        view: ViewKind,
+        skills: &[SkillSummary],
+        fetched_at: SystemTime,
+    ) -> Result<()> {
+        let entry = CachedView {
+            fetched_at: fetched_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            skills: skills.to_vec(),
+        };
+        let json = serde_json::to_vec(&entry).map_err(|e| AppError::Parse(e.to_string()))?;
+
+        std::fs::create_dir_all(&self.dir)?;
+        write_atomic(&self.path(view), &json)
+    }
+}
+
+/// Git blob id of `content`: the sha the git trees API reports for a file.
+pub fn git_blob_sha(content: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", content.len()).as_bytes());
+    hasher.update(content);
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Where a cached SKILL.md came from and how to revalidate it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentEntry {
+    /// Git blob sha of the document; also the name of its object file.
+    pub sha: String,
+    /// Path of the SKILL.md inside the repository.
+    pub path: String,
+    pub git_ref: String,
+    #[serde(default)]
+    pub etag: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ObjectEntry {
+    size: u64,
+    /// When the object was last stored or read, on a counter that only
+    /// grows, so uses within the same second still order correctly.
+    last_used: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DocumentIndex {
+    documents: BTreeMap<String, DocumentEntry>,
+    objects: BTreeMap<String, ObjectEntry>,
+}
+
+impl DocumentIndex {
+    /// A `last_used` value later than every recorded one.
+    fn tick(&self) -> u64 {
+        let latest = self.objects.values().map(|o| o.last_used).max();
+        latest.map_or(0, |t| t + 1)
+    }
+}
+
+/// Content-addressed on-disk cache of SKILL.md documents.
+///
+/// Documents are stored once per blob sha under `objects/`, and `index.json`
+/// maps each (source, skill id) to the blob it last resolved to. Objects are
+/// evicted least-recently-used first once the cache grows past `max_bytes`.
+#[derive(Debug, Clone)]
+pub struct DocumentCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    index: Arc<Mutex<DocumentIndex>>,
+}
+
+impl DocumentCache {
+    pub fn open(dir: PathBuf, max_bytes: u64) -> Self {
+        let index = std::fs::read(dir.join("index.json"))
+            .ok()
+            .and_then(|contents| serde_json::from_slice(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            dir,
+            max_bytes,
+            index: Arc::new(Mutex::new(index)),
+        }
+    }
+
+    fn object_path(&self, sha: &str) -> PathBuf {
+        self.dir.join("objects").join(format!("{}.md", sha))
+    }
+
+    fn document_key(key: &SkillKey) -> String {
+        format!("{}#{}", key.source, key.skill_id)
+    }
+
+    /// Contents of the blob `sha`, if it is cached.
+    pub fn object(&self, sha: &str) -> Option<String> {
+        let content = std::fs::read_to_string(self.object_path(sha)).ok()?;
+        let mut index = self.index.lock().unwrap();
+        let now = index.tick();
+        if let Some(object) = index.objects.get_mut(sha) {
+            object.last_used = now;
+        }
+        Some(content)
+    }
+
+    /// The document `key` last resolved to, with its contents.
+    pub fn lookup(&self, key: &SkillKey) -> Option<(DocumentEntry, String)> {
+        let entry = self
+            .index
+            .lock()
+            .unwrap()
+            .documents
+            .get(&Self::document_key(key))
+            .cloned()?;
+        let content = self.object(&entry.sha)?;
+        Some((entry, content))
+    }
+
+    /// Record that `key` resolves to `entry`, storing `content` under the
+    /// entry's sha if it is not cached yet.
+    pub fn store(&self, key: &SkillKey, entry: DocumentEntry, content: &str) -> Result<()> {
+        let object_path = self.object_path(&entry.sha);
+        if !object_path.exists() {
+            std::fs::create_dir_all(self.dir.join("objects"))?;
+            write_atomic(&object_path, content.as_bytes())?;
+        }
+
+        let mut index = self.index.lock().unwrap();
+        let now = index.tick();
+        index.objects.insert(
+            entry.sha.clone(),
+            ObjectEntry {
+                size: content.len() as u64,
+                last_used: now,
+            },
+        );
+        index.documents.insert(Self::document_key(key), entry);
+        self.evict(&mut index);
+
+        let json = serde_json::to_vec(&*index).map_err(|e| AppError::Parse(e.to_string()))?;
+        write_atomic(&self.dir.join("index.json"), &json)
+    }
+
+    fn evict(&self, index: &mut DocumentIndex) {
+        let mut total: u64 = index.objects.values().map(|o| o.size).sum();
+        if total <= self.max_bytes {
+            return;
+        }
+
+        let mut by_age: Vec<(String, u64, u64)> = index
+            .objects
+            .iter()
+            .map(|(sha, o)| (sha.clone(), o.last_used, o.size))
+            .collect();
+        by_age.sort_by_key(|&(_, last_used, _)| last_used);
+
+        for (sha, _, size) in by_age {
+            if total <= self.max_bytes {
+                break;
+            }
+            let _ = std::fs::remove_file(self.object_path(&sha));
+            index.objects.remove(&sha);
+            index.documents.retain(|_, doc| doc.sha != sha);
+            total -= size;
+        }
+    }
+}
+
+/// Write to a temporary file first so readers never see a partial file.
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
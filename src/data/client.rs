@@ -1,50 +1,291 @@
-use crate::data::{parse, SkillSummary, ViewKind};
+use crate::config::{Config, FetchMode};
+use crate::data::cache::{git_blob_sha, DocumentCache, DocumentEntry};
+use crate::data::dump::ResponseDump;
+use crate::data::git::GitCheckouts;
+use crate::data::host::{HostKind, HostSettings, Hosts, Repo, GITHUB_DOMAIN};
+use crate::data::parse::Leaderboard;
+use crate::data::plugin::{
+    Marketplace, MarketplaceManifest, PluginManifest, MARKETPLACE_PATH, PLUGIN_MANIFEST_PATH,
+};
+use crate::data::tree::{SkillEntry, SkillIndex, TreeEntry, TreeResponse};
+use crate::data::{parse, RateLimit, SkillDetail, SkillFile, SkillFiles, SkillKey, ViewKind};
 use crate::error::{AppError, Result};
-use reqwest::Client;
+use reqwest::header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use reqwest::{Client, StatusCode};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::sync::Semaphore;
+
+pub const DEFAULT_REGISTRY_URL: &str = "https://skills.sh";
+pub const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+pub const DEFAULT_GITHUB_RAW_URL: &str = "https://raw.githubusercontent.com";
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+pub const DEFAULT_USER_AGENT: &str = concat!("skillsm/", env!("CARGO_PKG_VERSION"));
 
 #[derive(Clone)]
 pub struct SkillsClient {
     client: Client,
-    base_url: String,
+    registry_url: String,
+    github_raw_url: String,
+    hosts: Hosts,
+    fetch_mode: FetchMode,
+    git: Arc<GitCheckouts>,
+    indexes: Arc<Mutex<HashMap<(String, String), SkillIndex>>>,
+    default_branches: Arc<Mutex<HashMap<String, String>>>,
+    documents: Option<DocumentCache>,
+    rate_limit: Arc<Mutex<Option<RateLimit>>>,
+    dump: Option<Arc<ResponseDump>>,
+}
+
+/// A SKILL.md located in a repository.
+#[derive(Debug, Clone)]
+pub struct FetchedDocument {
+    pub content: String,
+    /// Path of the SKILL.md inside the repository.
+    pub path: String,
+    /// Git blob sha of the document.
+    pub sha: String,
+    pub etag: Option<String>,
+}
+
+enum RawFile {
+    Modified {
+        content: String,
+        etag: Option<String>,
+    },
+    NotModified,
+    Missing,
+}
+
+/// Configures a [`SkillsClient`]: endpoints, HTTP settings and the optional
+/// caches. Every setting has a default matching the public services.
+#[derive(Clone, Default)]
+pub struct SkillsClientBuilder {
+    registry_url: Option<String>,
+    github_api_url: Option<String>,
+    github_raw_url: Option<String>,
+    timeout: Option<Duration>,
+    user_agent: Option<String>,
+    proxy: Option<String>,
+    ca_certificate: Option<PathBuf>,
+    github_token: Option<String>,
+    hosts: Vec<(String, HostSettings)>,
+    fetch_mode: FetchMode,
+    checkouts: Option<PathBuf>,
+    documents: Option<DocumentCache>,
+    dump: Option<Arc<ResponseDump>>,
+}
+
+impl SkillsClientBuilder {
+    /// A builder with the endpoint, HTTP and token settings of `config`,
+    /// including their environment overrides.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let network = &config.network;
+        let mut builder = Self::default().github_token(config.github.token());
+        if let Some(url) = network.registry_url() {
+            builder = builder.registry_url(url);
+        }
+        if let Some(url) = network.github_api_url() {
+            builder = builder.github_api_url(url);
+        }
+        if let Some(url) = network.github_raw_url() {
+            builder = builder.github_raw_url(url);
+        }
+        if let Some(timeout) = network.timeout()? {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(user_agent) = network.user_agent() {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(proxy) = network.proxy() {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(path) = network.ca_cert() {
+            builder = builder.ca_certificate(path);
+        }
+        for host in &config.hosts {
+            builder = builder.host(
+                host.domain.trim(),
+                HostSettings {
+                    kind: host.kind,
+                    api_url: host.api_url.clone(),
+                    token: host.token.clone(),
+                    clone_url: host.clone_url.clone(),
+                },
+            );
+        }
+        Ok(builder)
+    }
+
+    /// Base URL of the skills.sh leaderboard, e.g. an internal mirror.
+    pub fn registry_url(mut self, url: impl Into<String>) -> Self {
+        self.registry_url = Some(url.into());
+        self
+    }
+
+    /// Base URL of the GitHub REST API.
+    pub fn github_api_url(mut self, url: impl Into<String>) -> Self {
+        self.github_api_url = Some(url.into());
+        self
+    }
+
+    /// Base URL serving raw repository files.
+    pub fn github_raw_url(mut self, url: impl Into<String>) -> Self {
+        self.github_raw_url = Some(url.into());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Send every request through the proxy at `url`. Without one, the
+    /// standard `HTTPS_PROXY`/`HTTP_PROXY` variables are honoured.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Trust the PEM certificate(s) at `path` in addition to the built-in
+    /// roots.
+    pub fn ca_certificate(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca_certificate = Some(path.into());
+        self
+    }
+
+    /// Authenticate GitHub API requests with `token`, raising the rate limit
+    /// from 60 to 5000 requests per hour.
+    pub fn github_token(mut self, token: Option<String>) -> Self {
+        self.github_token = token.filter(|t| !t.trim().is_empty());
+        self
+    }
+
+    /// Declare the kind and credentials of the git server at `domain`, for
+    /// hosts that cannot be recognised by name.
+    pub fn host(mut self, domain: impl Into<String>, settings: HostSettings) -> Self {
+        self.hosts.push((domain.into(), settings));
+        self
+    }
+
+    /// Read repositories through their host API, or from git checkouts.
+    /// Hosts without a known API are always read with git.
+    pub fn fetch_mode(mut self, mode: FetchMode) -> Self {
+        self.fetch_mode = mode;
+        self
+    }
+
+    /// Keep git checkouts under `dir` instead of the system temp directory.
+    pub fn git_checkouts(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.checkouts = Some(dir.into());
+        self
+    }
+
+    /// Store fetched SKILL.md documents in `cache` and fall back to it when
+    /// offline.
+    pub fn document_cache(mut self, cache: DocumentCache) -> Self {
+        self.documents = Some(cache);
+        self
+    }
+
+    /// Save every response body the client receives to `dump`.
+    pub fn response_dump(mut self, dump: ResponseDump) -> Self {
+        self.dump = Some(Arc::new(dump));
+        self
+    }
+
+    pub fn build(self) -> Result<SkillsClient> {
+        let mut builder = Client::builder()
+            .timeout(self.timeout.unwrap_or(DEFAULT_TIMEOUT))
+            .user_agent(self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT));
+
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| AppError::Config(format!("invalid proxy {}: {}", proxy, e)))?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some(path) = &self.ca_certificate {
+            let pem = std::fs::read(path).map_err(|e| {
+                AppError::Config(format!("CA certificate {}: {}", path.display(), e))
+            })?;
+            let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| {
+                AppError::Config(format!("CA certificate {}: {}", path.display(), e))
+            })?;
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        let endpoint = |url: Option<String>, default: &str| {
+            url.unwrap_or_else(|| default.to_string())
+                .trim_end_matches('/')
+                .to_string()
+        };
+
+        let mut hosts = Hosts::new(
+            endpoint(self.github_api_url, DEFAULT_GITHUB_API_URL),
+            self.github_token,
+        );
+        for (domain, settings) in self.hosts {
+            hosts.declare(domain, settings);
+        }
+
+        Ok(SkillsClient {
+            client: builder.build()?,
+            registry_url: endpoint(self.registry_url, DEFAULT_REGISTRY_URL),
+            github_raw_url: endpoint(self.github_raw_url, DEFAULT_GITHUB_RAW_URL),
+            hosts,
+            fetch_mode: self.fetch_mode,
+            git: Arc::new(GitCheckouts::new(
+                self.checkouts
+                    .unwrap_or_else(|| std::env::temp_dir().join("skillsm-repos")),
+            )),
+            indexes: Arc::default(),
+            default_branches: Arc::default(),
+            documents: self.documents,
+            rate_limit: Arc::default(),
+            dump: self.dump,
+        })
+    }
 }
 
 impl SkillsClient {
+    /// A client for the public services with default settings.
     pub fn new() -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .user_agent("skillsm/0.1.0")
-            .build()?;
-
-        Ok(Self {
-            client,
-            base_url: "https://skills.sh".to_string(),
-        })
+        Self::builder().build()
     }
 
-    pub async fn fetch_skills(&self, view: ViewKind) -> Result<Vec<SkillSummary>> {
-        let url = match view {
-            ViewKind::AllTime => format!("{}/?view=all-time", self.base_url),
-            ViewKind::Trending => format!("{}/?view=trending", self.base_url),
-            ViewKind::Hot => format!("{}/?view=hot", self.base_url),
-        };
+    pub fn builder() -> SkillsClientBuilder {
+        SkillsClientBuilder::default()
+    }
+
+    /// Fetch a skills.sh leaderboard, along with any schema-drift warnings
+    /// raised while parsing it.
+    pub async fn fetch_skills(&self, view: ViewKind) -> Result<Leaderboard> {
+        let url = format!("{}/?view={}", self.registry_url, view.slug());
 
         let response = self.client.get(&url).send().await?;
 
         if !response.status().is_success() {
-            return Err(AppError::Parse(format!(
-                "HTTP error: {}",
-                response.status()
-            )));
+            return Err(AppError::from_status(
+                response.status(),
+                &url,
+                format!("skills.sh view {}", view.slug()),
+            ));
         }
 
-        let html = response.text().await?;
-        let mut skills = parse::extract_skills_from_html(&html)?;
+        let html = self.read_body(&url, response).await?;
+        let mut leaderboard = parse::extract_leaderboard(&html)?;
+        let skills = &mut leaderboard.skills;
 
         match view {
-            ViewKind::AllTime => {
-                skills.sort_by(|a, b| b.installs.cmp(&a.installs));
-            }
             ViewKind::Trending | ViewKind::Hot => {
                 skills.sort_by(|a, b| {
                     let a_change = a.change.unwrap_or(0);
@@ -52,177 +293,801 @@ impl SkillsClient {
                     b_change.cmp(&a_change)
                 });
             }
+            _ => {
+                skills.sort_by_key(|skill| std::cmp::Reverse(skill.installs));
+            }
+        }
+
+        Ok(leaderboard)
+    }
+
+    /// GET `url` and return its body, for documents hosted outside GitHub.
+    pub async fn fetch_text(&self, url: &str) -> Result<String> {
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(AppError::from_status(response.status(), url, url));
+        }
+        self.read_body(url, response).await
+    }
+
+    /// Read a response body as text, saving a copy when dumping is enabled.
+    async fn read_body(&self, url: &str, response: reqwest::Response) -> Result<String> {
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let body = response.text().await?;
+        if let Some(dump) = &self.dump {
+            dump.save(url, content_type.as_deref(), &body);
+        }
+        Ok(body)
+    }
+
+    /// GitHub API quota as reported by the most recent API response.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        *self.rate_limit.lock().unwrap()
+    }
+
+    /// The hosts skill sources are resolved against.
+    pub fn hosts(&self) -> &Hosts {
+        &self.hosts
+    }
+
+    /// A GET to `url` carrying `repo`'s host credentials, in the form its
+    /// API expects.
+    fn host_request(&self, repo: &Repo, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.get(url);
+        let Some(token) = &repo.token else {
+            return request;
+        };
+        match repo.kind {
+            HostKind::Github => request.bearer_auth(token),
+            HostKind::Gitlab => request.header("PRIVATE-TOKEN", token),
+            HostKind::Gitea => request.header("Authorization", format!("token {}", token)),
+            HostKind::Generic => request,
+        }
+    }
+
+    /// Send an authenticated GET to `repo`'s host API. GitHub rate-limit
+    /// headers are recorded, and an exhausted quota becomes
+    /// [`AppError::RateLimited`].
+    async fn api_get(&self, repo: &Repo, url: &str) -> Result<reqwest::Response> {
+        let mut request = self.host_request(repo, url);
+        if repo.kind != HostKind::Github {
+            return Ok(request.send().await?);
+        }
+        request = request.header("Accept", "application/vnd.github.v3+json");
+        let response = request.send().await?;
+
+        let rate_limit = RateLimit::from_headers(response.headers());
+        if let Some(rate_limit) = rate_limit {
+            *self.rate_limit.lock().unwrap() = Some(rate_limit);
+        }
+
+        let status = response.status();
+        if status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS {
+            if let Some(rate_limit) = rate_limit.filter(|r| r.remaining == 0) {
+                return Err(AppError::RateLimited {
+                    reset_at: rate_limit.reset_at,
+                });
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Resolve the ref to browse `source` at: the requested branch, tag or
+    /// commit, or the repository's default branch when none is given.
+    pub async fn resolve_ref(&self, source: &str, git_ref: Option<&str>) -> Result<String> {
+        match git_ref {
+            Some(git_ref) if !git_ref.trim().is_empty() => Ok(git_ref.trim().to_string()),
+            _ => self.default_branch(source).await,
         }
+    }
 
-        Ok(skills)
-    }
-
-    pub async fn fetch_readme(&self, source: &str, skill_id: &str) -> Result<String> {
-        // First try direct path with skill_id as folder name
-        let branches = ["main", "master"];
-        let path_templates = [
-            "skills/{}/SKILL.md",
-            ".skills/{}/SKILL.md",
-            ".claude/skills/{}/SKILL.md",
-        ];
-
-        for template in &path_templates {
-            for branch in &branches {
-                let path = template.replace("{}", skill_id);
-                let url = format!(
-                    "https://raw.githubusercontent.com/{}/{}/{}",
-                    source, branch, path
-                );
-                let response = self.client.get(&url).send().await?;
-                if response.status().is_success() {
-                    return Ok(response.text().await?);
+    /// Default branch of `source`, queried once and cached for the lifetime of
+    /// the client.
+    pub async fn default_branch(&self, source: &str) -> Result<String> {
+        if let Some(branch) = self.default_branches.lock().unwrap().get(source) {
+            return Ok(branch.clone());
+        }
+
+        let repo = self.hosts.resolve(source);
+        let branch = if self.uses_git(&repo) {
+            self.git.default_branch(&repo.clone_url()).await?
+        } else {
+            self.api_default_branch(&repo).await?
+        };
+
+        self.default_branches
+            .lock()
+            .unwrap()
+            .insert(source.to_string(), branch.clone());
+        Ok(branch)
+    }
+
+    async fn api_default_branch(&self, repo: &Repo) -> Result<String> {
+        let url = repo
+            .repo_api_url()
+            .ok_or_else(|| AppError::UnsupportedHost(repo.domain.clone()))?;
+        let response = self.api_get(repo, &url).await?;
+
+        if !response.status().is_success() {
+            return Err(AppError::from_status(
+                response.status(),
+                &url,
+                format!("repository {}", repo.path),
+            ));
+        }
+
+        let body = self.read_body(&url, response).await?;
+        let json: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| AppError::SchemaChanged(format!("repository response: {}", e)))?;
+        json["default_branch"]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| {
+                AppError::SchemaChanged(format!("no default_branch reported for {}", repo.path))
+            })
+    }
+
+    /// Whether `repo` is read from a git checkout rather than its host API.
+    fn uses_git(&self, repo: &Repo) -> bool {
+        self.fetch_mode == FetchMode::Git || repo.kind == HostKind::Generic
+    }
+
+    /// Local working tree of `source` at `git_ref`, shallow-fetched with git
+    /// on first use.
+    pub async fn checkout(&self, source: &str, git_ref: &str) -> Result<PathBuf> {
+        let repo = self.hosts.resolve(source);
+        let key = format!("{}/{}", repo.domain, repo.path);
+        self.git.checkout(&repo.clone_url(), &key, git_ref).await
+    }
+
+    /// Fetch and parse the SKILL.md of `key`, at `git_ref` or the default
+    /// branch. When the document cache is enabled, fetched documents are
+    /// stored in it. A cached copy is revalidated with one conditional
+    /// request before looking the skill up again, and served as is when the
+    /// host is unreachable.
+    pub async fn fetch_detail(&self, key: &SkillKey, git_ref: Option<&str>) -> Result<SkillDetail> {
+        if let Some(detail) = self.revalidate_cached(key, git_ref).await {
+            return Ok(detail);
+        }
+
+        let result = async {
+            let resolved = self.resolve_ref(&key.source, git_ref).await?;
+            let document = self.fetch_readme(key, &resolved).await?;
+            Ok::<_, AppError>((resolved, document))
+        }
+        .await;
+
+        match result {
+            Ok((resolved, document)) => {
+                if let Some(cache) = &self.documents {
+                    let entry = DocumentEntry {
+                        sha: document.sha,
+                        path: document.path,
+                        git_ref: resolved.clone(),
+                        etag: document.etag,
+                    };
+                    // A failed cache write only costs a later refetch.
+                    let _ = cache.store(key, entry, &document.content);
                 }
+                let mut detail = SkillDetail::from_markdown(document.content);
+                detail.git_ref = Some(resolved);
+                Ok(detail)
             }
+            // Only an unreachable host falls back to the cache; a skill that
+            // is gone upstream is reported as gone.
+            Err(e) if git_ref.is_none() && e.is_transient() => self.cached_detail(key).ok_or(e),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The cached SKILL.md of `key`, without touching the network.
+    pub fn cached_detail(&self, key: &SkillKey) -> Option<SkillDetail> {
+        let (entry, content) = self.documents.as_ref()?.lookup(key)?;
+        let mut detail = SkillDetail::from_markdown(content);
+        detail.git_ref = Some(entry.git_ref);
+        Some(detail)
+    }
+
+    /// Revalidate the cached document of `key` with a conditional request
+    /// for the file it was read from, skipping the ref and tree lookups. The
+    /// cached copy is served unchanged on a 304 or when the host cannot be
+    /// reached, and the new one when the file changed in place. `None` when
+    /// nothing is cached at `git_ref`, or the file is no longer there.
+    async fn revalidate_cached(
+        &self,
+        key: &SkillKey,
+        git_ref: Option<&str>,
+    ) -> Option<SkillDetail> {
+        let cache = self.documents.as_ref()?;
+        let (entry, cached) = cache.lookup(key)?;
+        if git_ref.is_some_and(|git_ref| git_ref.trim() != entry.git_ref) {
+            return None;
         }
 
-        // If not found, scan the skills directory for matching SKILL.md with name field
-        for skills_dir in ["skills", ".skills", ".claude/skills"] {
-            for branch in &branches {
-                if let Ok(folders) = self.list_skill_folders(source, branch, skills_dir).await {
-                    for folder in folders {
-                        let url = format!(
-                            "https://raw.githubusercontent.com/{}/{}/{}/{}/SKILL.md",
-                            source, branch, skills_dir, folder
-                        );
-                        let response = self.client.get(&url).send().await?;
-                        if response.status().is_success() {
-                            let content = response.text().await?;
-                            // Check if this SKILL.md has matching name in frontmatter
-                            if self.skill_matches_name(&content, skill_id) {
-                                return Ok(content);
-                            }
-                        }
-                    }
+        let content = match self
+            .fetch_raw(
+                &key.source,
+                &entry.git_ref,
+                &entry.path,
+                entry.etag.as_deref(),
+            )
+            .await
+        {
+            Ok(RawFile::Modified { content, etag }) => {
+                let updated = DocumentEntry {
+                    sha: git_blob_sha(content.as_bytes()),
+                    etag,
+                    ..entry.clone()
+                };
+                let _ = cache.store(key, updated, &content);
+                content
+            }
+            Ok(RawFile::NotModified) => cached,
+            Err(e) if e.is_transient() => cached,
+            Ok(RawFile::Missing) | Err(_) => return None,
+        };
+
+        let mut detail = SkillDetail::from_markdown(content);
+        detail.git_ref = Some(entry.git_ref);
+        Some(detail)
+    }
+
+    pub async fn fetch_readme(&self, key: &SkillKey, git_ref: &str) -> Result<FetchedDocument> {
+        let (source, skill_id) = (key.source.as_str(), key.skill_id.as_str());
+        let index = self.skill_index(source, git_ref).await?;
+
+        // Entries already known by frontmatter name or folder name need at
+        // most one request each.
+        let candidates = index.candidates(skill_id);
+        for &idx in &candidates {
+            let entry = &index.entries[idx];
+            if let Some(document) = self.fetch_entry(source, git_ref, entry).await? {
+                let name = self.record_manifest_name(source, git_ref, idx, &document.content);
+                if entry.folder == skill_id
+                    || entry.dir() == skill_id
+                    || name.as_deref() == Some(skill_id)
+                {
+                    return Ok(document);
                 }
             }
         }
+        // A folder path names one folder, which is not there.
+        if skill_id.contains('/') {
+            return Err(AppError::NotFound(format!("SKILL.md for {}", key)));
+        }
 
-        // Try searching in plugins (e.g., plugins/*/skills/{skill_id}/SKILL.md)
-        for branch in &branches {
-            if let Ok(content) = self
-                .search_plugins_for_skill(source, branch, skill_id)
-                .await
-            {
-                return Ok(content);
+        // Otherwise read the remaining SKILL.md files, a few at a time, until
+        // a frontmatter name matches, remembering every name seen along the
+        // way. Dropping the set cancels the reads still in flight.
+        let permits = Arc::new(Semaphore::new(FETCH_CONCURRENCY));
+        let mut tasks = tokio::task::JoinSet::new();
+        for idx in index.unnamed() {
+            if candidates.contains(&idx) {
+                continue;
+            }
+            let client = self.clone();
+            let permits = permits.clone();
+            let (source, git_ref) = (source.to_string(), git_ref.to_string());
+            let entry = index.entries[idx].clone();
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                (idx, client.fetch_entry(&source, &git_ref, &entry).await)
+            });
+        }
+        while let Some(joined) = tasks.join_next().await {
+            let (idx, document) = joined.map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+            if let Some(document) = document? {
+                let name = self.record_manifest_name(source, git_ref, idx, &document.content);
+                if name.as_deref() == Some(skill_id) {
+                    return Ok(document);
+                }
             }
         }
 
-        Err(AppError::Parse(format!(
-            "SKILL.md not found for {}/{}",
-            source, skill_id
-        )))
+        if index.truncated {
+            return Err(AppError::TreeTruncated(key.source.clone()));
+        }
+        Err(AppError::NotFound(format!("SKILL.md for {}", key)))
     }
 
-    async fn search_plugins_for_skill(
+    /// Frontmatter name of every entry of [`Self::skill_index`], in entry
+    /// order, read a few documents at a time. Entries whose document cannot
+    /// be read, or has no name, get `None`.
+    pub async fn skill_names(&self, source: &str, git_ref: &str) -> Result<Vec<Option<String>>> {
+        let index = self.skill_index(source, git_ref).await?;
+        let permits = Arc::new(Semaphore::new(FETCH_CONCURRENCY));
+        let mut tasks = tokio::task::JoinSet::new();
+        for (idx, entry) in index.entries.iter().cloned().enumerate() {
+            let client = self.clone();
+            let permits = permits.clone();
+            let (source, git_ref) = (source.to_string(), git_ref.to_string());
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                (idx, client.fetch_entry(&source, &git_ref, &entry).await)
+            });
+        }
+
+        let mut names = vec![None; index.entries.len()];
+        while let Some(joined) = tasks.join_next().await {
+            let (idx, document) = joined.map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+            if let Ok(Some(document)) = document {
+                names[idx] = self.record_manifest_name(source, git_ref, idx, &document.content);
+            }
+        }
+        Ok(names)
+    }
+
+    /// Contents of an indexed SKILL.md, read from the document cache when its
+    /// blob sha is already there.
+    async fn fetch_entry(
         &self,
         source: &str,
-        branch: &str,
-        skill_id: &str,
-    ) -> Result<String> {
-        // Try plugins directory (e.g., plugins/expo-app-design/skills/{skill_id})
-        if let Ok(plugins) = self.list_skill_folders(source, branch, "plugins").await {
-            for plugin in &plugins {
-                let skills_path = format!("plugins/{}/skills", plugin);
-                if let Ok(folders) = self.list_skill_folders(source, branch, &skills_path).await {
-                    for folder in folders {
-                        if folder == skill_id {
-                            let url = format!(
-                                "https://raw.githubusercontent.com/{}/{}/{}/{}/SKILL.md",
-                                source, branch, skills_path, folder
-                            );
-                            let response = self.client.get(&url).send().await?;
-                            if response.status().is_success() {
-                                return Ok(response.text().await?);
-                            }
-                        }
-                    }
+        git_ref: &str,
+        entry: &SkillEntry,
+    ) -> Result<Option<FetchedDocument>> {
+        if let Some(content) = self.documents.as_ref().and_then(|c| c.object(&entry.sha)) {
+            return Ok(Some(FetchedDocument {
+                content,
+                path: entry.path.clone(),
+                sha: entry.sha.clone(),
+                etag: None,
+            }));
+        }
+
+        match self.fetch_raw(source, git_ref, &entry.path, None).await? {
+            RawFile::Modified { content, etag } => Ok(Some(FetchedDocument {
+                content,
+                path: entry.path.clone(),
+                sha: entry.sha.clone(),
+                etag,
+            })),
+            RawFile::NotModified | RawFile::Missing => Ok(None),
+        }
+    }
+
+    /// Index of every SKILL.md in `source` at `git_ref`, fetched with a
+    /// recursive tree listing and cached for the lifetime of the client.
+    pub async fn skill_index(&self, source: &str, git_ref: &str) -> Result<SkillIndex> {
+        let cache_key = (source.to_string(), git_ref.to_string());
+        if let Some(index) = self.indexes.lock().unwrap().get(&cache_key) {
+            return Ok(index.clone());
+        }
+
+        let repo = self.hosts.resolve(source);
+        let tree = if self.uses_git(&repo) {
+            let dir = self.checkout(source, git_ref).await?;
+            self.git.tree(&dir).await?
+        } else {
+            self.fetch_tree(&repo, git_ref).await?
+        };
+        let index = SkillIndex::from_tree(tree, repo.name());
+
+        self.indexes
+            .lock()
+            .unwrap()
+            .insert(cache_key, index.clone());
+        Ok(index)
+    }
+
+    /// Recursive listing of `repo` at `git_ref`. GitHub answers in one
+    /// request; GitLab and Gitea page through the tree, up to
+    /// [`MAX_TREE_PAGES`] pages.
+    async fn fetch_tree(&self, repo: &Repo, git_ref: &str) -> Result<TreeResponse> {
+        let mut listing = TreeResponse {
+            sha: git_ref.to_string(),
+            tree: Vec::new(),
+            truncated: false,
+        };
+        for page in 1..=MAX_TREE_PAGES {
+            let url = repo
+                .tree_url(git_ref, page)
+                .ok_or_else(|| AppError::UnsupportedHost(repo.domain.clone()))?;
+            let response = self.api_get(repo, &url).await?;
+            if !response.status().is_success() {
+                return Err(AppError::from_status(
+                    response.status(),
+                    &url,
+                    format!("{} at {}", repo.path, git_ref),
+                ));
+            }
+            let next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| !v.trim().is_empty());
+            let body = self.read_body(&url, response).await?;
+            let schema_error =
+                |e: serde_json::Error| AppError::SchemaChanged(format!("tree response: {}", e));
+
+            let more = match repo.kind {
+                HostKind::Gitlab => {
+                    let entries: Vec<GitlabTreeEntry> =
+                        serde_json::from_str(&body).map_err(schema_error)?;
+                    listing
+                        .tree
+                        .extend(entries.into_iter().map(|entry| TreeEntry {
+                            path: entry.path,
+                            kind: entry.kind,
+                            sha: entry.id,
+                            size: None,
+                            mode: entry.mode,
+                        }));
+                    next_page
                 }
+                _ => {
+                    let tree: TreeResponse = serde_json::from_str(&body).map_err(schema_error)?;
+                    // Gitea flags every page but the last as truncated.
+                    let more =
+                        repo.kind == HostKind::Gitea && tree.truncated && !tree.tree.is_empty();
+                    listing.sha = tree.sha;
+                    listing.truncated = tree.truncated;
+                    listing.tree.extend(tree.tree);
+                    more
+                }
+            };
+            if !more {
+                return Ok(listing);
             }
         }
+        listing.truncated = true;
+        Ok(listing)
+    }
 
-        // Try plugins/claude/*/skills/{skill_id} pattern (e.g., plugins/claude/prompts.chat/skills/skill-lookup)
-        if let Ok(claude_plugins) = self
-            .list_skill_folders(source, branch, "plugins/claude")
-            .await
-        {
-            for plugin in claude_plugins {
-                let skills_path = format!("plugins/claude/{}/skills", plugin);
-                if let Ok(folders) = self.list_skill_folders(source, branch, &skills_path).await {
-                    for folder in folders {
-                        if folder == skill_id {
-                            let url = format!(
-                                "https://raw.githubusercontent.com/{}/{}/{}/{}/SKILL.md",
-                                source, branch, skills_path, folder
-                            );
-                            let response = self.client.get(&url).send().await?;
-                            if response.status().is_success() {
-                                return Ok(response.text().await?);
-                            }
-                        }
-                    }
+    /// The plugin marketplace of `source` at `git_ref`, read from its
+    /// `.claude-plugin` manifests, or `None` when the repository has none. A
+    /// repository with only a root `plugin.json` is a marketplace of one.
+    pub async fn fetch_marketplace(
+        &self,
+        source: &str,
+        git_ref: &str,
+    ) -> Result<Option<Marketplace>> {
+        let index = self.skill_index(source, git_ref).await?;
+        let repo = self.hosts.resolve(source);
+        let repo_name = repo.name();
+
+        let Some(json) = self
+            .fetch_text_file(source, git_ref, MARKETPLACE_PATH)
+            .await?
+        else {
+            return match self
+                .fetch_text_file(source, git_ref, PLUGIN_MANIFEST_PATH)
+                .await?
+            {
+                Some(json) => {
+                    let manifest: PluginManifest =
+                        parse_manifest_json(PLUGIN_MANIFEST_PATH, &json)?;
+                    Ok(Some(Marketplace::single(repo_name, manifest, &index)))
                 }
+                None => Ok(None),
+            };
+        };
+        let manifest: MarketplaceManifest = parse_manifest_json(MARKETPLACE_PATH, &json)?;
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for root in manifest.plugins.iter().filter_map(|plugin| plugin.root()) {
+            let client = self.clone();
+            let (source, git_ref) = (source.to_string(), git_ref.to_string());
+            tasks.spawn(async move {
+                let path = if root.is_empty() {
+                    PLUGIN_MANIFEST_PATH.to_string()
+                } else {
+                    format!("{}/{}", root, PLUGIN_MANIFEST_PATH)
+                };
+                let json = client.fetch_text_file(&source, &git_ref, &path).await;
+                (root, json)
+            });
+        }
+        let mut plugin_manifests = HashMap::new();
+        while let Some(joined) = tasks.join_next().await {
+            let Ok((root, json)) = joined else { continue };
+            // A missing or malformed plugin.json only costs that plugin the
+            // metadata it does not repeat in the marketplace entry.
+            if let Some(manifest) = json
+                .ok()
+                .flatten()
+                .and_then(|json| serde_json::from_str::<PluginManifest>(&json).ok())
+            {
+                plugin_manifests.insert(root, manifest);
             }
         }
 
-        Err(AppError::Parse("Not found in plugins".to_string()))
+        Ok(Some(Marketplace::resolve(
+            manifest,
+            &plugin_manifests,
+            &index,
+        )))
     }
 
-    async fn list_skill_folders(
+    /// A file of `source` at `git_ref`, or `None` when it does not exist.
+    async fn fetch_text_file(
         &self,
         source: &str,
-        branch: &str,
-        skills_dir: &str,
-    ) -> Result<Vec<String>> {
-        let url = format!(
-            "https://api.github.com/repos/{}/contents/{}?ref={}",
-            source, skills_dir, branch
-        );
-        let response = self
-            .client
-            .get(&url)
-            .header("Accept", "application/vnd.github.v3+json")
-            .send()
-            .await?;
+        git_ref: &str,
+        path: &str,
+    ) -> Result<Option<String>> {
+        match self.fetch_raw(source, git_ref, path, None).await? {
+            RawFile::Modified { content, .. } => Ok(Some(content)),
+            RawFile::NotModified | RawFile::Missing => Ok(None),
+        }
+    }
 
+    async fn fetch_raw(
+        &self,
+        source: &str,
+        git_ref: &str,
+        path: &str,
+        etag: Option<&str>,
+    ) -> Result<RawFile> {
+        let repo = self.hosts.resolve(source);
+        if self.uses_git(&repo) {
+            return self.read_checkout_file(source, git_ref, path).await;
+        }
+        let (url, mut request) = self.raw_request(&repo, git_ref, path)?;
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = request.send().await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(RawFile::NotModified);
+        }
         if !response.status().is_success() {
-            return Err(AppError::Parse(
-                "Failed to list skills directory".to_string(),
-            ));
+            return Ok(RawFile::Missing);
         }
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        Ok(RawFile::Modified {
+            content: self.read_body(&url, response).await?,
+            etag,
+        })
+    }
 
-        let items: Vec<serde_json::Value> = response
-            .json()
-            .await
-            .map_err(|e| AppError::Parse(e.to_string()))?;
-
-        Ok(items
-            .iter()
-            .filter(|item| item["type"].as_str() == Some("dir"))
-            .filter_map(|item| item["name"].as_str().map(String::from))
-            .collect())
-    }
-
-    fn skill_matches_name(&self, content: &str, skill_id: &str) -> bool {
-        // Parse YAML frontmatter to check name field
-        if let Some(start) = content.find("---") {
-            if let Some(end) = content[start + 3..].find("---") {
-                let frontmatter = &content[start + 3..start + 3 + end];
-                for line in frontmatter.lines() {
-                    let line = line.trim();
-                    if line.starts_with("name:") {
-                        let name = line.trim_start_matches("name:").trim();
-                        return name == skill_id;
-                    }
-                }
+    /// GET for the raw contents of `path` in `repo`, and its URL.
+    fn raw_request(
+        &self,
+        repo: &Repo,
+        git_ref: &str,
+        path: &str,
+    ) -> Result<(String, reqwest::RequestBuilder)> {
+        let url = repo
+            .raw_url(&self.github_raw_url, git_ref, path)
+            .ok_or_else(|| AppError::UnsupportedHost(repo.domain.clone()))?;
+        let request = match repo.kind {
+            // raw.githubusercontent.com serves public files without a token.
+            HostKind::Github if repo.domain == GITHUB_DOMAIN => self.client.get(&url),
+            HostKind::Github => self
+                .host_request(repo, &url)
+                .header("Accept", "application/vnd.github.raw"),
+            _ => self.host_request(repo, &url),
+        };
+        Ok((url, request))
+    }
+
+    /// Every file of the folder holding `key`'s SKILL.md, at `git_ref` or the
+    /// default branch, found the same way as [`Self::fetch_readme`].
+    pub async fn fetch_skill_files(
+        &self,
+        key: &SkillKey,
+        git_ref: Option<&str>,
+    ) -> Result<SkillFiles> {
+        let resolved = self.resolve_ref(&key.source, git_ref).await?;
+        let document = self.fetch_readme(key, &resolved).await?;
+        let dir = document
+            .path
+            .rsplit_once('/')
+            .map_or("", |(dir, _)| dir)
+            .to_string();
+        let index = self.skill_index(&key.source, &resolved).await?;
+        let paths = index.folder_files(&dir);
+        if paths.len() > MAX_SKILL_FILES {
+            return Err(AppError::InvalidSkill(format!(
+                "{} has {} files, more than the {} a skill may have",
+                key,
+                paths.len(),
+                MAX_SKILL_FILES
+            )));
+        }
+
+        let relative = move |path: &str| -> String {
+            if dir.is_empty() {
+                path.to_string()
+            } else {
+                path[dir.len() + 1..].to_string()
             }
+        };
+        let mut files = vec![SkillFile {
+            path: relative(&document.path),
+            contents: document.content.into_bytes(),
+            executable: false,
+        }];
+        let permits = Arc::new(Semaphore::new(FETCH_CONCURRENCY));
+        let mut tasks = tokio::task::JoinSet::new();
+        for path in paths.into_iter().filter(|p| *p != document.path) {
+            let client = self.clone();
+            let permits = permits.clone();
+            let (source, git_ref) = (key.source.clone(), resolved.clone());
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                let contents = client.fetch_file_bytes(&source, &git_ref, &path).await;
+                (path, contents)
+            });
+        }
+        while let Some(joined) = tasks.join_next().await {
+            let (path, contents) = joined.map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+            files.push(SkillFile {
+                path: relative(&path),
+                contents: contents?,
+                executable: index.is_executable(&path),
+            });
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        // Only needed to show what changed since, so a failed lookup is no
+        // reason to fail the install.
+        let commit = self.head_commit(&key.source, &resolved).await.ok();
+        Ok(SkillFiles {
+            git_ref: Some(resolved),
+            commit,
+            files,
+        })
+    }
+
+    /// Commit `git_ref` of `source` points at.
+    pub async fn head_commit(&self, source: &str, git_ref: &str) -> Result<String> {
+        let repo = self.hosts.resolve(source);
+        if self.uses_git(&repo) {
+            let dir = self.checkout(source, git_ref).await?;
+            return self.git.head(&dir).await;
+        }
+
+        let url = repo
+            .commit_url(git_ref)
+            .ok_or_else(|| AppError::UnsupportedHost(repo.domain.clone()))?;
+        let response = self.api_get(&repo, &url).await?;
+        if !response.status().is_success() {
+            return Err(AppError::from_status(
+                response.status(),
+                &url,
+                format!("{} at {}", repo.path, git_ref),
+            ));
+        }
+        let body = self.read_body(&url, response).await?;
+        let json: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| AppError::SchemaChanged(format!("commit response: {}", e)))?;
+        let sha = match repo.kind {
+            HostKind::Gitlab => &json["id"],
+            HostKind::Gitea => &json[0]["sha"],
+            _ => &json["sha"],
+        };
+        sha.as_str().map(String::from).ok_or_else(|| {
+            AppError::SchemaChanged(format!(
+                "no commit reported for {} at {}",
+                repo.path, git_ref
+            ))
+        })
+    }
+
+    /// Contents of a file of `source` at `git_ref`, as bytes so assets
+    /// survive unchanged.
+    async fn fetch_file_bytes(&self, source: &str, git_ref: &str, path: &str) -> Result<Vec<u8>> {
+        let repo = self.hosts.resolve(source);
+        if self.uses_git(&repo) {
+            let dir = self.checkout(source, git_ref).await?;
+            return read_checkout(&dir, path)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("{} in {}", path, source)));
+        }
+        let (url, request) = self.raw_request(&repo, git_ref, path)?;
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(AppError::from_status(
+                response.status(),
+                &url,
+                format!("{} in {}", path, source),
+            ));
         }
-        false
+        Ok(response.bytes().await?.to_vec())
     }
+
+    /// A file of the git checkout of `source` at `git_ref`, read as
+    /// [`read_checkout`] does.
+    async fn read_checkout_file(&self, source: &str, git_ref: &str, path: &str) -> Result<RawFile> {
+        let dir = self.checkout(source, git_ref).await?;
+        let Some(bytes) = read_checkout(&dir, path).await? else {
+            return Ok(RawFile::Missing);
+        };
+        let content = String::from_utf8(bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(RawFile::Modified {
+            content,
+            etag: None,
+        })
+    }
+
+    /// Parse the frontmatter name of a fetched SKILL.md and record it in the
+    /// cached index so later lookups can go straight to it.
+    fn record_manifest_name(
+        &self,
+        source: &str,
+        git_ref: &str,
+        idx: usize,
+        content: &str,
+    ) -> Option<String> {
+        let (frontmatter, _) = parse::split_frontmatter(content);
+        let name = frontmatter.and_then(|fm| parse::parse_manifest(fm).name);
+
+        let cache_key = (source.to_string(), git_ref.to_string());
+        if let Some(index) = self.indexes.lock().unwrap().get_mut(&cache_key) {
+            index.record_name(idx, name.as_deref());
+        }
+        name
+    }
+}
+
+/// Files fetched at once while searching a repository or downloading a
+/// skill, and skills checked at once for updates.
+pub(crate) const FETCH_CONCURRENCY: usize = 8;
+
+/// Files a skill folder may hold before installing it is refused.
+const MAX_SKILL_FILES: usize = 500;
+
+/// `path` inside the checkout at `dir`, unless it would leave it.
+fn checkout_path(dir: &std::path::Path, path: &str) -> Option<PathBuf> {
+    let relative = std::path::Path::new(path);
+    relative
+        .components()
+        .all(|c| matches!(c, std::path::Component::Normal(_)))
+        .then(|| dir.join(relative))
+}
+
+/// Contents of the regular file `path` in the checkout at `dir`. Paths that
+/// leave the working tree, symlinks and anything else that is not a plain
+/// file count as missing, so a repository cannot point an install at files
+/// elsewhere on this machine.
+async fn read_checkout(dir: &std::path::Path, path: &str) -> Result<Option<Vec<u8>>> {
+    let Some(file) = checkout_path(dir, path) else {
+        return Ok(None);
+    };
+    let metadata = match tokio::fs::symlink_metadata(&file).await {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    if !metadata.is_file() {
+        return Ok(None);
+    }
+    // A symlinked parent folder would also lead outside the checkout.
+    let real = tokio::fs::canonicalize(&file).await?;
+    if !real.starts_with(tokio::fs::canonicalize(dir).await?) {
+        return Ok(None);
+    }
+    Ok(Some(tokio::fs::read(&real).await?))
+}
+
+/// Pages of a tree listing read before giving up and reporting it truncated.
+const MAX_TREE_PAGES: u32 = 100;
+
+/// One entry of a GitLab repository tree response.
+#[derive(serde::Deserialize)]
+struct GitlabTreeEntry {
+    id: String,
+    path: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    mode: Option<String>,
+}
+
+fn parse_manifest_json<T: serde::de::DeserializeOwned>(path: &str, json: &str) -> Result<T> {
+    serde_json::from_str(json).map_err(|e| AppError::Parse(format!("{}: {}", path, e)))
 }
 
 impl Default for SkillsClient {
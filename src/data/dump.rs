@@ -0,0 +1,55 @@
+//! Saving raw responses to disk so schema changes can be attached to bug
+//! reports (`--dump-responses <dir>`).
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Writes each response body to `<dir>/<seq>-<url>.<ext>`.
+#[derive(Debug)]
+pub struct ResponseDump {
+    dir: PathBuf,
+    next: AtomicUsize,
+}
+
+impl ResponseDump {
+    pub fn new(dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            next: AtomicUsize::new(1),
+        })
+    }
+
+    /// Save `body`, fetched from `url`. Failures are ignored: a missing dump
+    /// must never break the request that produced it.
+    pub fn save(&self, url: &str, content_type: Option<&str>, body: &str) {
+        let seq = self.next.fetch_add(1, Ordering::Relaxed);
+        let name = format!("{:04}-{}.{}", seq, file_stem(url), extension(content_type));
+        let _ = std::fs::write(self.dir.join(name), body);
+    }
+}
+
+/// `url` without its scheme, with anything but `[A-Za-z0-9._-]` replaced.
+fn file_stem(url: &str) -> String {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let stem: String = url
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    stem.chars().take(120).collect()
+}
+
+fn extension(content_type: Option<&str>) -> &'static str {
+    match content_type {
+        Some(ct) if ct.contains("json") => "json",
+        Some(ct) if ct.contains("html") => "html",
+        _ => "txt",
+    }
+}
@@ -0,0 +1,207 @@
+//! Decoding of the React Server Components ("flight") payload that Next.js
+//! streams into its pages as `self.__next_f.push([...])` script calls.
+
+use serde_json::Value;
+
+const PUSH_MARKER: &str = "self.__next_f.push(";
+
+/// Concatenate the text chunks pushed into `self.__next_f`, in page order.
+///
+/// Each call pushes an array whose first element is the chunk type; type 1
+/// chunks carry the flight payload as a JavaScript string literal. Returns
+/// `None` when the page contains no flight chunks.
+pub fn payload(html: &str) -> Option<String> {
+    let mut payload = String::new();
+    let mut found = false;
+
+    let mut rest = html;
+    while let Some(pos) = rest.find(PUSH_MARKER) {
+        rest = &rest[pos + PUSH_MARKER.len()..];
+        if let Some((kind, text)) = parse_push_args(rest) {
+            found = true;
+            if kind == 1 {
+                if let Some(text) = text {
+                    payload.push_str(&text);
+                }
+            }
+        }
+    }
+
+    found.then_some(payload)
+}
+
+/// Parse `[<int>]` or `[<int>, "<string>"]` at the start of `input`.
+fn parse_push_args(input: &str) -> Option<(u32, Option<String>)> {
+    let input = input.trim_start().strip_prefix('[')?.trim_start();
+    let digits = input.find(|c: char| !c.is_ascii_digit())?;
+    let kind: u32 = input[..digits].parse().ok()?;
+    let input = input[digits..].trim_start();
+
+    if input.starts_with(']') {
+        return Some((kind, None));
+    }
+    let input = input.strip_prefix(',')?.trim_start();
+    let (text, _) = decode_js_string(input)?;
+    Some((kind, Some(text)))
+}
+
+/// Decode the JavaScript string literal at the start of `input`, returning
+/// the decoded text and the number of bytes consumed, quotes included.
+pub fn decode_js_string(input: &str) -> Option<(String, usize)> {
+    let quote = input.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    let mut out = String::new();
+    let mut chars = input.char_indices().skip(1);
+
+    while let Some((idx, c)) = chars.next() {
+        if c == quote {
+            return Some((out, idx + c.len_utf8()));
+        }
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        let (_, escaped) = chars.next()?;
+        match escaped {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            'b' => out.push('\u{8}'),
+            'f' => out.push('\u{c}'),
+            'v' => out.push('\u{b}'),
+            '0' => out.push('\0'),
+            // Line continuation.
+            '\n' => {}
+            '\r' => {
+                let mut lookahead = chars.clone();
+                if matches!(lookahead.next(), Some((_, '\n'))) {
+                    chars = lookahead;
+                }
+            }
+            'x' => {
+                let hex: String = (0..2)
+                    .filter_map(|_| chars.next().map(|(_, c)| c))
+                    .collect();
+                out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            'u' => {
+                let code = read_unicode_escape(&mut chars)?;
+                // Combine UTF-16 surrogate pairs written as two escapes.
+                if (0xD800..0xDC00).contains(&code) {
+                    let mut lookahead = chars.clone();
+                    let is_pair = matches!(lookahead.next(), Some((_, '\\')))
+                        && matches!(lookahead.next(), Some((_, 'u')));
+                    if is_pair {
+                        if let Some(low) = read_unicode_escape(&mut lookahead) {
+                            if (0xDC00..0xE000).contains(&low) {
+                                chars = lookahead;
+                                let combined = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                                out.push(char::from_u32(combined)?);
+                                continue;
+                            }
+                        }
+                    }
+                }
+                out.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+            other => out.push(other),
+        }
+    }
+
+    None
+}
+
+/// Read the digits of a `\uXXXX` or `\u{X...}` escape (after the `u`).
+fn read_unicode_escape(chars: &mut std::iter::Skip<std::str::CharIndices<'_>>) -> Option<u32> {
+    let mut lookahead = chars.clone();
+    let hex: String = if matches!(lookahead.next(), Some((_, '{'))) {
+        *chars = lookahead;
+        let mut hex = String::new();
+        for (_, c) in chars.by_ref() {
+            if c == '}' {
+                break;
+            }
+            hex.push(c);
+        }
+        hex
+    } else {
+        (0..4)
+            .filter_map(|_| chars.next().map(|(_, c)| c))
+            .collect()
+    };
+    u32::from_str_radix(&hex, 16).ok()
+}
+
+/// Every JSON value in a flight payload.
+///
+/// The payload is a sequence of `<hex id>:<row>` records. JSON rows (optionally
+/// tagged with a letter prefix such as `I` or `HL`) end at a newline, while
+/// text rows are written as `T<hex length>,<text>` with no terminator.
+pub fn json_rows(payload: &str) -> Vec<Value> {
+    let mut values = Vec::new();
+    let mut rest = payload;
+
+    while !rest.is_empty() {
+        let Some(colon) = rest.find(':') else {
+            break;
+        };
+        let id = &rest[..colon];
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+            // Not at a row boundary; resynchronise on the next line.
+            match rest.find('\n') {
+                Some(nl) => {
+                    rest = &rest[nl + 1..];
+                    continue;
+                }
+                None => break,
+            }
+        }
+        rest = &rest[colon + 1..];
+
+        if let Some(text_row) = rest.strip_prefix('T') {
+            // Text rows carry a byte length instead of a terminator.
+            let Some(comma) = text_row.find(',') else {
+                break;
+            };
+            let len = usize::from_str_radix(&text_row[..comma], 16).unwrap_or(0);
+            let body = &text_row[comma + 1..];
+            rest = body.get(len..).unwrap_or("");
+            continue;
+        }
+
+        let end = rest.find('\n').unwrap_or(rest.len());
+        let row = rest[..end].trim_start_matches(|c: char| c.is_ascii_alphabetic());
+        if let Ok(value) = serde_json::from_str::<Value>(row) {
+            values.push(value);
+        }
+        rest = rest.get(end + 1..).unwrap_or("");
+    }
+
+    values
+}
+
+/// Depth-first search for arrays of objects that all contain `keys`.
+pub fn find_object_arrays<'a>(value: &'a Value, keys: &[&str], out: &mut Vec<&'a Vec<Value>>) {
+    match value {
+        Value::Array(items) => {
+            let matches = !items.is_empty()
+                && items.iter().all(|item| {
+                    item.as_object()
+                        .is_some_and(|obj| keys.iter().all(|k| obj.contains_key(*k)))
+                });
+            if matches {
+                out.push(items);
+            } else {
+                for item in items {
+                    find_object_arrays(item, keys, out);
+                }
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values() {
+                find_object_arrays(item, keys, out);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,174 @@
+//! Repositories read through the system `git` instead of a host API: each ref
+//! is shallow-fetched into its own working tree under a cache directory, and
+//! skills are read from disk. Works with any remote git understands,
+//! including `file://`.
+
+use crate::data::host::encode_component;
+use crate::data::tree::{TreeEntry, TreeResponse, SYMLINK_MODE};
+use crate::error::{AppError, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use tokio::process::Command;
+
+/// Working trees of fetched repositories, one per repository and ref.
+#[derive(Debug)]
+pub struct GitCheckouts {
+    root: PathBuf,
+    /// Checkouts brought up to date during this session.
+    synced: Mutex<HashSet<PathBuf>>,
+    /// Serialises git operations so concurrent lookups fetch only once.
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl GitCheckouts {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            synced: Mutex::default(),
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Branch the remote's `HEAD` points at.
+    pub async fn default_branch(&self, url: &str) -> Result<String> {
+        positional("repository", url)?;
+        let output = git(None, &["ls-remote", "--symref", "--", url, "HEAD"]).await?;
+        output
+            .lines()
+            .find_map(|line| {
+                let target = line.strip_prefix("ref:")?.split('\t').next()?.trim();
+                target.strip_prefix("refs/heads/").map(String::from)
+            })
+            .ok_or_else(|| AppError::Git(format!("{} has no default branch", url)))
+    }
+
+    /// Working tree of `url` at `git_ref`, fetched on first use in this
+    /// session. `key` names the repository in the cache directory.
+    pub async fn checkout(&self, url: &str, key: &str, git_ref: &str) -> Result<PathBuf> {
+        positional("repository", url)?;
+        positional("ref", git_ref)?;
+        let dir = self.root.join(format!(
+            "{}@{}",
+            encode_component(key),
+            encode_component(git_ref)
+        ));
+        let _guard = self.lock.lock().await;
+        if self.synced.lock().unwrap().contains(&dir) {
+            return Ok(dir);
+        }
+
+        if !dir.join(".git").is_dir() {
+            tokio::fs::create_dir_all(&dir).await?;
+            git(Some(&dir), &["init", "--quiet"]).await?;
+        }
+        let fetched = git(
+            Some(&dir),
+            &[
+                "fetch",
+                "--quiet",
+                "--depth",
+                "1",
+                "--no-tags",
+                "--",
+                url,
+                git_ref,
+            ],
+        )
+        .await;
+        match fetched {
+            Ok(_) => {
+                git(
+                    Some(&dir),
+                    &["checkout", "--quiet", "--force", "--detach", "FETCH_HEAD"],
+                )
+                .await?;
+            }
+            Err(e) => {
+                // Offline, an earlier checkout is better than nothing.
+                let has_checkout = git(Some(&dir), &["rev-parse", "--verify", "--quiet", "HEAD"])
+                    .await
+                    .is_ok();
+                if !has_checkout {
+                    return Err(e);
+                }
+            }
+        }
+
+        self.synced.lock().unwrap().insert(dir.clone());
+        Ok(dir)
+    }
+
+    /// Commit checked out at `dir`.
+    pub async fn head(&self, dir: &Path) -> Result<String> {
+        let sha = git(Some(dir), &["rev-parse", "HEAD"]).await?;
+        Ok(sha.trim().to_string())
+    }
+
+    /// Every file and folder of the checkout at `dir`, shaped like a git trees
+    /// API response.
+    pub async fn tree(&self, dir: &Path) -> Result<TreeResponse> {
+        let sha = git(Some(dir), &["rev-parse", "HEAD"]).await?;
+        let listing = git(Some(dir), &["ls-tree", "-r", "-t", "-z", "HEAD"]).await?;
+        let tree = listing
+            .split('\0')
+            .filter_map(|line| {
+                let (meta, path) = line.split_once('\t')?;
+                let mut meta = meta.split(' ');
+                let (mode, kind, sha) = (meta.next()?, meta.next()?, meta.next()?);
+                if mode == SYMLINK_MODE {
+                    return None;
+                }
+                Some(TreeEntry {
+                    path: path.to_string(),
+                    kind: kind.to_string(),
+                    sha: sha.to_string(),
+                    size: None,
+                    mode: Some(mode.to_string()),
+                })
+            })
+            .collect();
+        Ok(TreeResponse {
+            sha: sha.trim().to_string(),
+            tree,
+            truncated: false,
+        })
+    }
+}
+
+/// Refuse a url or ref git would read as an option, such as
+/// `--upload-pack=<command>`. They come from config files and receipts, and
+/// are passed after `--` as well.
+fn positional(what: &str, value: &str) -> Result<()> {
+    if value.starts_with('-') {
+        return Err(AppError::Git(format!("invalid {} {:?}", what, value)));
+    }
+    Ok(())
+}
+
+/// Run git, returning its stdout. Prompts are disabled: a TUI cannot answer
+/// them.
+async fn git(dir: Option<&Path>, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        command.arg("-C").arg(dir);
+    }
+    let output = command
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|e| AppError::Git(format!("could not run git: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::Git(format!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            stderr.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
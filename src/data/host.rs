@@ -0,0 +1,299 @@
+//! Git hosts serving skill repositories. A `source` is `owner/repo` on
+//! GitHub, or `<domain>/<path>` anywhere else, e.g. `gitlab.com/group/repo`
+//! or `codeberg.org/owner/repo`. Each host kind has its own API for default
+//! branches, recursive tree listings and raw files.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub const GITHUB_DOMAIN: &str = "github.com";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HostKind {
+    /// github.com or GitHub Enterprise.
+    Github,
+    /// gitlab.com or a self-managed GitLab.
+    Gitlab,
+    /// Gitea or Forgejo, such as Codeberg.
+    Gitea,
+    /// Any other git host; no API is available.
+    Generic,
+}
+
+impl HostKind {
+    /// Kind of a host not listed in the config, guessed from its domain.
+    fn guess(domain: &str) -> Self {
+        match domain {
+            GITHUB_DOMAIN => HostKind::Github,
+            "gitlab.com" => HostKind::Gitlab,
+            "codeberg.org" => HostKind::Gitea,
+            _ if domain.contains("gitlab") => HostKind::Gitlab,
+            _ if domain.contains("gitea") || domain.contains("forgejo") => HostKind::Gitea,
+            _ => HostKind::Generic,
+        }
+    }
+}
+
+/// A host declared in the config.
+#[derive(Debug, Clone)]
+pub struct HostSettings {
+    pub kind: HostKind,
+    /// API base URL, when it is not at the kind's usual location.
+    pub api_url: Option<String>,
+    pub token: Option<String>,
+    /// Base of clone URLs, when not `https://<domain>`.
+    pub clone_url: Option<String>,
+}
+
+/// A repository on a specific host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repo {
+    pub kind: HostKind,
+    pub domain: String,
+    /// Repository path on the host: `owner/repo`, or `group/sub/repo` on
+    /// GitLab.
+    pub path: String,
+    pub api_url: String,
+    pub token: Option<String>,
+    clone_base: Option<String>,
+}
+
+impl Repo {
+    /// Page of the repository on its host.
+    pub fn web_url(&self) -> String {
+        format!("https://{}/{}", self.domain, self.path)
+    }
+
+    pub fn clone_url(&self) -> String {
+        match &self.clone_base {
+            Some(base) => format!("{}/{}", base, self.path),
+            None => format!("{}.git", self.web_url()),
+        }
+    }
+
+    /// Last path segment.
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    /// URL describing the repository, including its default branch.
+    pub fn repo_api_url(&self) -> Option<String> {
+        match self.kind {
+            HostKind::Github | HostKind::Gitea => {
+                Some(format!("{}/repos/{}", self.api_url, self.path))
+            }
+            HostKind::Gitlab => Some(format!(
+                "{}/projects/{}",
+                self.api_url,
+                encode_component(&self.path)
+            )),
+            HostKind::Generic => None,
+        }
+    }
+
+    /// URL of one page of the recursive tree listing at `git_ref`. GitHub
+    /// answers in a single page; `page` starts at 1.
+    pub fn tree_url(&self, git_ref: &str, page: u32) -> Option<String> {
+        match self.kind {
+            HostKind::Github => Some(format!(
+                "{}/repos/{}/git/trees/{}?recursive=1",
+                self.api_url,
+                self.path,
+                encode_component(git_ref)
+            )),
+            HostKind::Gitea => Some(format!(
+                "{}/repos/{}/git/trees/{}?recursive=true&per_page=1000&page={}",
+                self.api_url,
+                self.path,
+                encode_component(git_ref),
+                page
+            )),
+            HostKind::Gitlab => Some(format!(
+                "{}/projects/{}/repository/tree?recursive=true&ref={}&per_page=100&page={}",
+                self.api_url,
+                encode_component(&self.path),
+                encode_component(git_ref),
+                page
+            )),
+            HostKind::Generic => None,
+        }
+    }
+
+    /// URL describing the commit `git_ref` points at. Gitea only lists
+    /// commits, so its answer is an array of one.
+    pub fn commit_url(&self, git_ref: &str) -> Option<String> {
+        match self.kind {
+            HostKind::Github => Some(format!(
+                "{}/repos/{}/commits/{}",
+                self.api_url,
+                self.path,
+                encode_component(git_ref)
+            )),
+            HostKind::Gitea => Some(format!(
+                "{}/repos/{}/commits?sha={}&limit=1&stat=false",
+                self.api_url,
+                self.path,
+                encode_component(git_ref)
+            )),
+            HostKind::Gitlab => Some(format!(
+                "{}/projects/{}/repository/commits/{}",
+                self.api_url,
+                encode_component(&self.path),
+                encode_component(git_ref)
+            )),
+            HostKind::Generic => None,
+        }
+    }
+
+    /// Web page comparing the commits `from` and `to`.
+    pub fn compare_url(&self, from: &str, to: &str) -> Option<String> {
+        match self.kind {
+            HostKind::Github | HostKind::Gitea => {
+                Some(format!("{}/compare/{}...{}", self.web_url(), from, to))
+            }
+            HostKind::Gitlab => Some(format!("{}/-/compare/{}...{}", self.web_url(), from, to)),
+            HostKind::Generic => None,
+        }
+    }
+
+    /// URL returning the raw contents of `file` at `git_ref`. `raw_base`
+    /// serves github.com files.
+    pub fn raw_url(&self, raw_base: &str, git_ref: &str, file: &str) -> Option<String> {
+        match self.kind {
+            HostKind::Github if self.domain == GITHUB_DOMAIN => Some(format!(
+                "{}/{}/{}/{}",
+                raw_base,
+                self.path,
+                encode_path(git_ref),
+                encode_path(file)
+            )),
+            // GitHub Enterprise serves raw files through the contents API.
+            HostKind::Github => Some(format!(
+                "{}/repos/{}/contents/{}?ref={}",
+                self.api_url,
+                self.path,
+                encode_path(file),
+                encode_component(git_ref)
+            )),
+            HostKind::Gitea => Some(format!(
+                "{}/repos/{}/raw/{}?ref={}",
+                self.api_url,
+                self.path,
+                encode_path(file),
+                encode_component(git_ref)
+            )),
+            HostKind::Gitlab => Some(format!(
+                "{}/projects/{}/repository/files/{}/raw?ref={}",
+                self.api_url,
+                encode_component(&self.path),
+                encode_component(file),
+                encode_component(git_ref)
+            )),
+            HostKind::Generic => None,
+        }
+    }
+}
+
+/// Known hosts, by domain. Hosts missing from the config are recognised by
+/// their domain where possible.
+#[derive(Debug, Clone, Default)]
+pub struct Hosts {
+    github_api_url: String,
+    github_token: Option<String>,
+    declared: HashMap<String, HostSettings>,
+}
+
+impl Hosts {
+    pub fn new(github_api_url: impl Into<String>, github_token: Option<String>) -> Self {
+        Self {
+            github_api_url: github_api_url.into(),
+            github_token,
+            declared: HashMap::new(),
+        }
+    }
+
+    pub fn declare(&mut self, domain: impl Into<String>, settings: HostSettings) {
+        self.declared
+            .insert(domain.into().to_ascii_lowercase(), settings);
+    }
+
+    /// The repository a skill `source` refers to.
+    pub fn resolve(&self, source: &str) -> Repo {
+        let (domain, path) = split_source(source);
+        let declared = self.declared.get(&domain);
+        let kind = declared.map_or_else(|| HostKind::guess(&domain), |host| host.kind);
+
+        let api_url = match declared.and_then(|host| host.api_url.clone()) {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None => match kind {
+                HostKind::Github if domain == GITHUB_DOMAIN => self.github_api_url.clone(),
+                HostKind::Github => format!("https://{}/api/v3", domain),
+                HostKind::Gitlab => format!("https://{}/api/v4", domain),
+                HostKind::Gitea => format!("https://{}/api/v1", domain),
+                HostKind::Generic => String::new(),
+            },
+        };
+        let token = match declared.and_then(|host| host.token.clone()) {
+            Some(token) => Some(token),
+            None if domain == GITHUB_DOMAIN => self.github_token.clone(),
+            None => None,
+        };
+
+        let clone_base = declared
+            .and_then(|host| host.clone_url.as_deref())
+            .map(|url| url.trim_end_matches('/').to_string());
+
+        Repo {
+            kind,
+            domain,
+            path,
+            api_url,
+            token,
+            clone_base,
+        }
+    }
+}
+
+/// `owner/repo` is on GitHub; a first segment that looks like a domain names
+/// the host.
+fn split_source(source: &str) -> (String, String) {
+    let source = source.trim_matches('/');
+    match source.split_once('/') {
+        Some((first, rest)) if first.contains('.') && rest.contains('/') => {
+            (first.to_ascii_lowercase(), rest.to_string())
+        }
+        _ => (GITHUB_DOMAIN.to_string(), source.to_string()),
+    }
+}
+
+/// The `source` for `path` on `domain`: bare for GitHub, prefixed otherwise.
+pub fn source_for(domain: &str, path: &str) -> String {
+    if domain.eq_ignore_ascii_case(GITHUB_DOMAIN) {
+        path.to_string()
+    } else {
+        format!("{}/{}", domain.to_ascii_lowercase(), path)
+    }
+}
+
+/// Percent-encode everything but unreserved characters, for path segments
+/// and query values.
+pub fn encode_component(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// Percent-encode each `/`-separated segment of `path`, keeping the slashes.
+pub fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(encode_component)
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,264 @@
+//! Static `index.json` catalogs, for team registries published on a file
+//! share. The schema is versioned; see `INDEX_VERSION`.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "skills": [
+//!     {
+//!       "source": "team/pdf",
+//!       "skillId": "pdf",
+//!       "name": "PDF Tools",
+//!       "description": "Work with PDF files",
+//!       "installs": 0,
+//!       "content": "team/pdf/SKILL.md"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `content` is an `http(s)://` or `file://` URL, or a path relative to the
+//! index file. `description` and `installs` are optional.
+
+use crate::data::local;
+use crate::data::registry::SkillRegistry;
+use crate::data::{Leaderboard, SkillDetail, SkillKey, SkillSummary, SkillsClient, ViewKind};
+use crate::error::{AppError, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The index schema version this build reads and writes.
+pub const INDEX_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryIndex {
+    pub version: u32,
+    pub skills: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexEntry {
+    pub source: String,
+    pub skill_id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub installs: i64,
+    /// Where the SKILL.md lives: a URL, or a path relative to the index.
+    pub content: String,
+}
+
+impl IndexEntry {
+    pub fn key(&self) -> SkillKey {
+        SkillKey::new(&self.source, &self.skill_id)
+    }
+
+    pub fn summary(&self) -> SkillSummary {
+        SkillSummary {
+            source: self.source.clone(),
+            skill_id: self.skill_id.clone(),
+            name: self.name.clone(),
+            installs: self.installs,
+            installs_yesterday: None,
+            change: None,
+            extra: BTreeMap::new(),
+        }
+    }
+}
+
+impl RegistryIndex {
+    /// Read an index from a filesystem path or `file://` URL.
+    pub fn load(location: &str) -> Result<Self> {
+        let path = location_path(location)?;
+        let contents = std::fs::read_to_string(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => AppError::NotFound(format!("index {}", path.display())),
+            _ => e.into(),
+        })?;
+        Self::parse(&contents)
+    }
+
+    /// Parse index JSON, rejecting versions newer than [`INDEX_VERSION`].
+    pub fn parse(json: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| AppError::Parse(format!("index.json: {}", e)))?;
+        let version = value["version"]
+            .as_u64()
+            .ok_or_else(|| AppError::Parse("index.json: missing \"version\"".to_string()))?;
+        if version > INDEX_VERSION as u64 {
+            return Err(AppError::Parse(format!(
+                "index.json version {} is newer than the supported version {}",
+                version, INDEX_VERSION
+            )));
+        }
+        serde_json::from_value(value).map_err(|e| AppError::Parse(format!("index.json: {}", e)))
+    }
+
+    /// Index every SKILL.md under `dir`. Content paths are written relative
+    /// to `base`, the directory the index will be saved in, or as absolute
+    /// paths when `dir` is not inside it.
+    pub fn build(dir: &Path, base: &Path) -> Result<Self> {
+        let base = base.canonicalize()?;
+        let skills = local::scan(dir)?
+            .into_iter()
+            .map(|skill| {
+                let path = skill.path.canonicalize()?;
+                let content = match path.strip_prefix(&base) {
+                    Ok(relative) => relative
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/"),
+                    Err(_) => path.display().to_string(),
+                };
+                Ok(IndexEntry {
+                    source: skill.summary.source,
+                    skill_id: skill.summary.skill_id,
+                    name: skill.summary.name,
+                    description: skill.manifest.description,
+                    installs: 0,
+                    content,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            version: INDEX_VERSION,
+            skills,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        let mut json =
+            serde_json::to_string_pretty(self).map_err(|e| AppError::Parse(e.to_string()))?;
+        json.push('\n');
+        Ok(json)
+    }
+}
+
+/// Filesystem path of an index location: a plain path or a `file://` URL.
+pub fn location_path(location: &str) -> Result<PathBuf> {
+    if location.starts_with("file://") {
+        return reqwest::Url::parse(location)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .ok_or_else(|| AppError::Config(format!("invalid file URL {}", location)));
+    }
+    if location.contains("://") {
+        return Err(AppError::Config(format!(
+            "index location {} must be a path or file:// URL",
+            location
+        )));
+    }
+    Ok(PathBuf::from(location))
+}
+
+/// A registry listing the skills of an `index.json`. The index is re-read on
+/// every refresh; documents are read from disk or fetched over HTTP.
+pub struct IndexRegistry {
+    id: String,
+    name: String,
+    location: String,
+    client: SkillsClient,
+    content: Mutex<HashMap<SkillKey, String>>,
+}
+
+impl IndexRegistry {
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        location: impl Into<String>,
+        client: SkillsClient,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            location: location.into(),
+            client,
+            content: Mutex::default(),
+        }
+    }
+
+    async fn load(&self) -> Result<RegistryIndex> {
+        let location = self.location.clone();
+        let index = tokio::task::spawn_blocking(move || RegistryIndex::load(&location))
+            .await
+            .map_err(|e| AppError::Io(std::io::Error::other(e)))??;
+        *self.content.lock().unwrap() = index
+            .skills
+            .iter()
+            .map(|entry| (entry.key(), entry.content.clone()))
+            .collect();
+        Ok(index)
+    }
+}
+
+#[async_trait]
+impl SkillRegistry for IndexRegistry {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn views(&self) -> Vec<ViewKind> {
+        vec![ViewKind::Catalog]
+    }
+
+    async fn list(&self, _view: ViewKind) -> Result<Leaderboard> {
+        let index = self.load().await?;
+        let mut skills: Vec<SkillSummary> = index.skills.iter().map(IndexEntry::summary).collect();
+        skills.sort_by_key(|skill| std::cmp::Reverse(skill.installs));
+        Ok(Leaderboard {
+            skills,
+            warnings: Vec::new(),
+        })
+    }
+
+    async fn fetch_detail(&self, key: &SkillKey, _git_ref: Option<&str>) -> Result<SkillDetail> {
+        let cached = self.content.lock().unwrap().get(key).cloned();
+        let content = match cached {
+            Some(content) => content,
+            None => {
+                let index = self.load().await?;
+                index
+                    .skills
+                    .into_iter()
+                    .find(|entry| entry.key() == *key)
+                    .map(|entry| entry.content)
+                    .ok_or_else(|| AppError::NotFound(format!("{} in {}", key, self.location)))?
+            }
+        };
+
+        let markdown = if content.starts_with("http://") || content.starts_with("https://") {
+            self.client.fetch_text(&content).await?
+        } else {
+            let path = if content.starts_with("file://") {
+                location_path(&content)?
+            } else {
+                let index_path = location_path(&self.location)?;
+                index_path.parent().unwrap_or(Path::new(".")).join(&content)
+            };
+            tokio::fs::read_to_string(&path)
+                .await
+                .map_err(|e| match e.kind() {
+                    std::io::ErrorKind::NotFound => AppError::NotFound(path.display().to_string()),
+                    _ => e.into(),
+                })?
+        };
+        Ok(SkillDetail::from_markdown(markdown))
+    }
+
+    fn install_command(&self, _skill: &SkillSummary) -> Option<Vec<String>> {
+        None
+    }
+
+    fn caches_views(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,395 @@
+//! A registry listing the skills already installed on this machine, found by
+//! scanning every install target. Copies of one skill in several targets
+//! share a row, whose install count is the number of copies.
+//!
+//! Its Updates view asks where each skill's receipt says it came from for
+//! the commit its ref points at now. Skills still at the installed commit
+//! are up to date; the others are fetched again and listed when their files
+//! no longer match a local copy.
+
+use crate::data::client::FETCH_CONCURRENCY;
+use crate::data::registry::SkillRegistry;
+use crate::data::{
+    Leaderboard, SkillDetail, SkillFiles, SkillKey, SkillSummary, SkillsClient, ViewKind,
+};
+use crate::error::{AppError, Result};
+use crate::install::{self, InstallTarget, InstalledSkill, Receipt};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+
+/// Skills found in the install targets on this machine.
+pub const INSTALLED_VIEW: ViewKind = ViewKind::Custom {
+    slug: "installed",
+    label: "Installed",
+};
+
+/// Installed skills whose upstream files have changed.
+pub const UPDATES_VIEW: ViewKind = ViewKind::Custom {
+    slug: "updates",
+    label: "Updates",
+};
+
+/// Registry id of the installed skills, reserved among configured registries.
+pub const INSTALLED_ID: &str = "installed";
+
+/// Length commits are abbreviated to.
+const SHORT_SHA: usize = 7;
+
+pub struct InstalledRegistry {
+    targets: Vec<InstallTarget>,
+    /// Registries updates are fetched from, matched by receipt registry id.
+    upstream: Vec<Arc<dyn SkillRegistry>>,
+    /// Fetches skills whose registry is not among `upstream`, such as
+    /// repositories opened in an earlier session.
+    client: Option<SkillsClient>,
+    /// Copies found by the last scan.
+    skills: Mutex<Vec<InstalledSkill>>,
+    /// Outdated skills found by the last update check.
+    updates: Mutex<Vec<Update>>,
+}
+
+/// An installed skill whose upstream files differ from a local copy.
+#[derive(Debug, Clone)]
+pub struct Update {
+    pub key: SkillKey,
+    pub name: String,
+    /// The copies that differ.
+    pub copies: Vec<InstalledSkill>,
+    pub upstream: SkillFiles,
+    /// Commit the first copy was installed from, where recorded.
+    pub installed_commit: Option<String>,
+    /// Page comparing the installed and upstream commits.
+    pub compare_url: Option<String>,
+}
+
+impl Update {
+    /// Commits between the installed copy and upstream, e.g. `1a2b3c4..5d6e7f8`.
+    pub fn range(&self) -> String {
+        match (&self.installed_commit, &self.upstream.commit) {
+            (Some(from), Some(to)) if from == to => "changed locally".to_string(),
+            (Some(from), Some(to)) => format!("{}..{}", short(from), short(to)),
+            (None, Some(to)) => format!("..{}", short(to)),
+            _ => "changed upstream".to_string(),
+        }
+    }
+}
+
+fn short(sha: &str) -> &str {
+    sha.get(..SHORT_SHA).unwrap_or(sha)
+}
+
+impl InstalledRegistry {
+    pub fn new(targets: Vec<InstallTarget>) -> Self {
+        Self {
+            targets,
+            upstream: Vec::new(),
+            client: None,
+            skills: Mutex::default(),
+            updates: Mutex::default(),
+        }
+    }
+
+    /// Check for updates through `registries`, falling back to `client`.
+    pub fn upstream(
+        mut self,
+        registries: Vec<Arc<dyn SkillRegistry>>,
+        client: SkillsClient,
+    ) -> Self {
+        self.upstream = registries;
+        self.client = Some(client);
+        self
+    }
+
+    /// Installed copies of `key`, in target order, as matched by
+    /// [`InstalledSkill::is_copy_of`].
+    pub fn copies(&self, key: &SkillKey) -> Vec<InstalledSkill> {
+        self.skills
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|skill| skill.is_copy_of(key))
+            .cloned()
+            .collect()
+    }
+
+    /// Outdated skills found by the last update check.
+    pub fn updates(&self) -> Vec<Update> {
+        self.updates.lock().unwrap().clone()
+    }
+
+    fn update(&self, key: &SkillKey) -> Option<Update> {
+        self.updates
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|update| update.key == *key)
+            .cloned()
+    }
+
+    /// Scan every target, remembering what was found.
+    async fn scan(&self) -> Result<(Vec<InstalledSkill>, Vec<String>)> {
+        let targets = self.targets.clone();
+        let (found, warnings) = tokio::task::spawn_blocking(move || {
+            let mut found = Vec::new();
+            let mut warnings = Vec::new();
+            for target in &targets {
+                match install::installed_in(target) {
+                    Ok(skills) => found.extend(skills),
+                    Err(e) => warnings.push(format!("{}: {}", target.dir.display(), e)),
+                }
+            }
+            (found, warnings)
+        })
+        .await
+        .map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+        *self.skills.lock().unwrap() = found.clone();
+        Ok((found, warnings))
+    }
+
+    async fn list_installed(&self) -> Result<Leaderboard> {
+        let (found, warnings) = self.scan().await?;
+        let mut rows: BTreeMap<SkillKey, SkillSummary> = BTreeMap::new();
+        for skill in &found {
+            let key = skill.key();
+            let row = rows
+                .entry(key.clone())
+                .or_insert_with(|| summary(key, skill));
+            row.installs += 1;
+        }
+
+        let mut skills: Vec<SkillSummary> = rows.into_values().collect();
+        skills.sort_by_key(|skill| skill.name.to_lowercase());
+        Ok(Leaderboard { skills, warnings })
+    }
+
+    /// Check every skill with a receipt against upstream, a few at a time.
+    async fn list_updates(&self) -> Result<Leaderboard> {
+        let (found, mut warnings) = self.scan().await?;
+        let mut groups: BTreeMap<SkillKey, Vec<InstalledSkill>> = BTreeMap::new();
+        for skill in found.into_iter().filter(|skill| skill.receipt.is_some()) {
+            groups.entry(skill.key()).or_default().push(skill);
+        }
+
+        let permits = Arc::new(Semaphore::new(FETCH_CONCURRENCY));
+        let mut tasks = tokio::task::JoinSet::new();
+        for (key, copies) in groups {
+            let upstream = self.upstream.clone();
+            let client = self.client.clone();
+            let permits = permits.clone();
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                let result = check(&upstream, client.as_ref(), &key, copies).await;
+                (key, result)
+            });
+        }
+        let mut updates = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            let (key, result) = joined.map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+            match result {
+                Ok(Some(update)) => updates.push(update),
+                Ok(None) => {}
+                Err(e) => warnings.push(format!("{}: {}", key, e)),
+            }
+        }
+        updates.sort_by_key(|update| update.name.to_lowercase());
+
+        let skills = updates
+            .iter()
+            .map(|update| {
+                let mut row = summary(update.key.clone(), &update.copies[0]);
+                row.installs = update.copies.len() as i64;
+                row
+            })
+            .collect();
+        *self.updates.lock().unwrap() = updates;
+        Ok(Leaderboard { skills, warnings })
+    }
+}
+
+fn summary(key: SkillKey, skill: &InstalledSkill) -> SkillSummary {
+    SkillSummary {
+        source: key.source,
+        skill_id: key.skill_id,
+        name: skill
+            .manifest
+            .name
+            .clone()
+            .unwrap_or_else(|| skill.folder()),
+        installs: 0,
+        installs_yesterday: None,
+        change: None,
+        extra: BTreeMap::new(),
+    }
+}
+
+/// The update for `copies` of `key`, or `None` when upstream has not moved
+/// since the install or every copy matches it.
+async fn check(
+    upstream: &[Arc<dyn SkillRegistry>],
+    client: Option<&SkillsClient>,
+    key: &SkillKey,
+    copies: Vec<InstalledSkill>,
+) -> Result<Option<Update>> {
+    let Some(receipt) = copies[0].receipt.clone() else {
+        return Ok(None);
+    };
+    if let (Some(client), Some(git_ref), Some(commit)) = (client, &receipt.git_ref, &receipt.commit)
+    {
+        // One request instead of downloading every file. A failed lookup
+        // falls through to comparing the files.
+        if matches!(client.head_commit(&receipt.source, git_ref).await, Ok(head) if head == *commit)
+        {
+            return Ok(None);
+        }
+    }
+    let files = upstream_files(upstream, client, &receipt).await?;
+    let hash = install::content_hash(&files.files);
+
+    let stale = tokio::task::spawn_blocking(move || {
+        copies
+            .into_iter()
+            .filter(|copy| install::folder_hash(&copy.dir).map_or(true, |h| h != hash))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+    let Some(first) = stale.first() else {
+        return Ok(None);
+    };
+
+    let installed_commit = first.receipt.as_ref().and_then(|r| r.commit.clone());
+    let compare_url = match (client, &installed_commit, &files.commit) {
+        (Some(client), Some(from), Some(to)) if from != to => client
+            .hosts()
+            .resolve(&receipt.source)
+            .compare_url(from, to),
+        _ => None,
+    };
+    Ok(Some(Update {
+        key: key.clone(),
+        name: first
+            .manifest
+            .name
+            .clone()
+            .unwrap_or_else(|| first.folder()),
+        copies: stale,
+        upstream: files,
+        installed_commit,
+        compare_url,
+    }))
+}
+
+/// The current files of the skill `receipt` describes, at the ref it was
+/// installed from.
+async fn upstream_files(
+    upstream: &[Arc<dyn SkillRegistry>],
+    client: Option<&SkillsClient>,
+    receipt: &Receipt,
+) -> Result<SkillFiles> {
+    let key = receipt.key();
+    let git_ref = receipt.git_ref.as_deref();
+    if let Some(registry) = upstream.iter().find(|r| r.id() == receipt.registry) {
+        return registry.skill_files(&key, git_ref).await;
+    }
+    match client {
+        Some(client) => client.fetch_skill_files(&key, git_ref).await,
+        None => Err(AppError::NotFound(format!("registry {}", receipt.registry))),
+    }
+}
+
+#[async_trait]
+impl SkillRegistry for InstalledRegistry {
+    fn id(&self) -> &str {
+        INSTALLED_ID
+    }
+
+    fn name(&self) -> &str {
+        "Installed"
+    }
+
+    fn views(&self) -> Vec<ViewKind> {
+        vec![INSTALLED_VIEW, UPDATES_VIEW]
+    }
+
+    async fn list(&self, view: ViewKind) -> Result<Leaderboard> {
+        if view == UPDATES_VIEW {
+            self.list_updates().await
+        } else {
+            self.list_installed().await
+        }
+    }
+
+    async fn fetch_detail(&self, key: &SkillKey, _git_ref: Option<&str>) -> Result<SkillDetail> {
+        let Some(skill) = self.copies(key).into_iter().next() else {
+            return Err(AppError::NotFound(key.to_string()));
+        };
+        let content = tokio::fs::read_to_string(skill.dir.join("SKILL.md")).await?;
+        let mut detail = SkillDetail::from_markdown(content);
+        detail.git_ref = skill.receipt.and_then(|receipt| receipt.git_ref);
+        Ok(detail)
+    }
+
+    fn installable(&self, _skill: &SkillSummary) -> bool {
+        false
+    }
+
+    fn install_command(&self, _skill: &SkillSummary) -> Option<Vec<String>> {
+        None
+    }
+
+    fn list_note(&self, skill: &SkillSummary) -> Option<String> {
+        let key = skill.key();
+        let mut note = self
+            .copies(&key)
+            .iter()
+            .map(|copy| copy.target.id())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if let Some(update) = self.update(&key) {
+            note.push_str(&format!(" · update {}", update.range()));
+        }
+        Some(note).filter(|note| !note.is_empty())
+    }
+
+    fn detail_fields(&self, skill: &SkillSummary) -> Vec<(&'static str, String)> {
+        let key = skill.key();
+        let copies = self.copies(&key);
+        let origin = match copies.first().and_then(|copy| copy.receipt.as_ref()) {
+            Some(receipt) => format!("{} (from {})", receipt.source, receipt.registry),
+            None => "unknown, not installed by skillsm".to_string(),
+        };
+        let mut fields = vec![("Origin", origin)];
+        for copy in copies {
+            fields.push((
+                "Location",
+                format!("{} ({})", copy.dir.display(), copy.target.id()),
+            ));
+        }
+        if let Some(update) = self.update(&key) {
+            fields.push(("Update", update.range()));
+            if let Some(url) = update.compare_url {
+                fields.push(("Changes", url));
+            }
+        }
+        fields
+    }
+
+    fn caches_views(&self) -> bool {
+        false
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    fn has_installed(&self, skill: &SkillSummary) -> bool {
+        let key = skill.key();
+        self.skills
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|copy| copy.may_be_copy_of(&key))
+    }
+}
@@ -0,0 +1,224 @@
+//! A registry backed by a directory tree of SKILL.md files, for skills that
+//! are never published to skills.sh.
+
+use crate::data::parse;
+use crate::data::registry::SkillRegistry;
+use crate::data::{
+    Leaderboard, SkillDetail, SkillFile, SkillFiles, SkillKey, SkillManifest, SkillSummary,
+    ViewKind,
+};
+use crate::error::{AppError, Result};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Directories never descended into while scanning.
+const SKIPPED_DIRS: [&str; 2] = ["node_modules", "target"];
+
+/// A SKILL.md found while scanning a directory tree.
+#[derive(Debug, Clone)]
+pub struct LocalSkill {
+    pub summary: SkillSummary,
+    pub manifest: SkillManifest,
+    /// Path of the SKILL.md file.
+    pub path: PathBuf,
+}
+
+/// Skills found under `root`. Each skill's `source` is the path of its folder
+/// relative to `root`, so keys stay unique however the tree is laid out.
+pub struct LocalRegistry {
+    id: String,
+    name: String,
+    root: PathBuf,
+}
+
+impl LocalRegistry {
+    pub fn new(id: impl Into<String>, name: impl Into<String>, root: impl Into<PathBuf>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            root: root.into(),
+        }
+    }
+
+    fn document_path(&self, key: &SkillKey) -> PathBuf {
+        self.root.join(&key.source).join("SKILL.md")
+    }
+}
+
+#[async_trait]
+impl SkillRegistry for LocalRegistry {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn views(&self) -> Vec<ViewKind> {
+        vec![ViewKind::Catalog]
+    }
+
+    async fn list(&self, _view: ViewKind) -> Result<Leaderboard> {
+        let root = self.root.clone();
+        let skills = tokio::task::spawn_blocking(move || scan(&root))
+            .await
+            .map_err(|e| AppError::Io(std::io::Error::other(e)))??;
+        Ok(Leaderboard {
+            skills: skills.into_iter().map(|skill| skill.summary).collect(),
+            warnings: Vec::new(),
+        })
+    }
+
+    async fn fetch_detail(&self, key: &SkillKey, _git_ref: Option<&str>) -> Result<SkillDetail> {
+        let path = self.document_path(key);
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) => Ok(SkillDetail::from_markdown(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(AppError::NotFound(path.display().to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn skill_files(&self, key: &SkillKey, _git_ref: Option<&str>) -> Result<SkillFiles> {
+        let dir = self.root.join(&key.source);
+        if !dir.join("SKILL.md").is_file() {
+            return Err(AppError::NotFound(dir.display().to_string()));
+        }
+        let files = tokio::task::spawn_blocking(move || read_folder(&dir))
+            .await
+            .map_err(|e| AppError::Io(std::io::Error::other(e)))??;
+        Ok(SkillFiles {
+            git_ref: None,
+            commit: None,
+            files,
+        })
+    }
+
+    fn install_command(&self, _skill: &SkillSummary) -> Option<Vec<String>> {
+        None
+    }
+
+    fn caches_views(&self) -> bool {
+        false
+    }
+}
+
+/// Every SKILL.md under `root`, sorted by name. Hidden directories and
+/// dependency folders are skipped, and symlinks are not followed.
+pub fn scan(root: &Path) -> Result<Vec<LocalSkill>> {
+    if !root.is_dir() {
+        return Err(AppError::NotFound(format!(
+            "skills directory {}",
+            root.display()
+        )));
+    }
+
+    let mut skills = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+
+            if file_type.is_dir() {
+                if !file_name.starts_with('.') && !SKIPPED_DIRS.contains(&file_name.as_ref()) {
+                    pending.push(entry.path());
+                }
+            } else if file_type.is_file() && file_name == "SKILL.md" {
+                if let Some(skill) = read_skill(root, &dir) {
+                    skills.push(skill);
+                }
+            }
+        }
+    }
+
+    skills.sort_by_key(|skill| skill.summary.name.to_lowercase());
+    Ok(skills)
+}
+
+/// Every file of the skill folder `dir`, skipping the same directories as
+/// [`scan`] and nested folders holding skills of their own.
+pub fn read_folder(dir: &Path) -> Result<Vec<SkillFile>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            let path = entry.path();
+
+            if file_type.is_dir() {
+                if !file_name.starts_with('.')
+                    && !SKIPPED_DIRS.contains(&file_name.as_ref())
+                    && !path.join("SKILL.md").is_file()
+                {
+                    pending.push(path);
+                }
+            } else if file_type.is_file() {
+                let Ok(relative) = path.strip_prefix(dir) else {
+                    continue;
+                };
+                let relative = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push(SkillFile {
+                    path: relative,
+                    contents: std::fs::read(&path)?,
+                    executable: is_executable(&entry.metadata()?),
+                });
+            }
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &std::fs::Metadata) -> bool {
+    false
+}
+
+fn read_skill(root: &Path, dir: &Path) -> Option<LocalSkill> {
+    let relative = dir.strip_prefix(root).ok()?;
+    let source = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    let folder = dir.file_name()?.to_string_lossy().into_owned();
+
+    let path = dir.join("SKILL.md");
+    let content = std::fs::read_to_string(&path).ok()?;
+    let (frontmatter, _) = parse::split_frontmatter(&content);
+    let manifest = frontmatter.map(parse::parse_manifest).unwrap_or_default();
+    let name = manifest.name.clone().unwrap_or_else(|| folder.clone());
+
+    Some(LocalSkill {
+        summary: SkillSummary {
+            source,
+            skill_id: folder,
+            name,
+            installs: 0,
+            installs_yesterday: None,
+            change: None,
+            extra: BTreeMap::new(),
+        },
+        manifest,
+        path,
+    })
+}
@@ -1,6 +1,29 @@
+pub mod cache;
 pub mod client;
+pub mod dump;
+pub mod flight;
+pub mod git;
+pub mod host;
+pub mod index;
+pub mod installed;
+pub mod local;
 pub mod models;
 pub mod parse;
+pub mod plugin;
+pub mod registry;
+pub mod repo;
+pub mod tree;
 
-pub use client::SkillsClient;
-pub use models::{SkillDetail, SkillSummary, ViewKind};
+pub use cache::{DocumentCache, ViewCache};
+pub use client::{SkillsClient, SkillsClientBuilder};
+pub use dump::ResponseDump;
+pub use host::{HostKind, Hosts, Repo};
+pub use index::{IndexRegistry, RegistryIndex};
+pub use installed::InstalledRegistry;
+pub use local::LocalRegistry;
+pub use models::{
+    RateLimit, SkillDetail, SkillFile, SkillFiles, SkillKey, SkillManifest, SkillSummary, ViewKind,
+};
+pub use parse::Leaderboard;
+pub use registry::{SkillRegistry, SkillsShRegistry};
+pub use repo::{RepoReference, RepoRegistry};
@@ -1,4 +1,8 @@
+use crate::data::parse;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -11,14 +15,37 @@ pub struct SkillSummary {
     pub installs_yesterday: Option<i64>,
     #[serde(default)]
     pub change: Option<i64>,
+    /// Fields skills.sh sent that this version does not know about.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, serde_json::Value>,
 }
 
 impl SkillSummary {
-    pub fn install_command(&self) -> String {
-        format!(
-            "npx skills add https://github.com/{} --skill {}",
-            self.source, self.skill_id
-        )
+    pub fn key(&self) -> SkillKey {
+        SkillKey::new(&self.source, &self.skill_id)
+    }
+}
+
+/// Identifies a skill across repositories: skill ids are only unique within
+/// a source, so two repositories may publish skills with the same id.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SkillKey {
+    pub source: String,
+    pub skill_id: String,
+}
+
+impl SkillKey {
+    pub fn new(source: impl Into<String>, skill_id: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            skill_id: skill_id.into(),
+        }
+    }
+}
+
+impl fmt::Display for SkillKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.source, self.skill_id)
     }
 }
 
@@ -28,6 +55,14 @@ pub enum ViewKind {
     AllTime,
     Trending,
     Hot,
+    /// Every skill of a registry without leaderboards, such as a local
+    /// directory.
+    Catalog,
+    /// A view only one registry offers, declared as a constant next to it.
+    Custom {
+        slug: &'static str,
+        label: &'static str,
+    },
 }
 
 impl ViewKind {
@@ -36,31 +71,101 @@ impl ViewKind {
             ViewKind::AllTime => "All Time",
             ViewKind::Trending => "Trending (24h)",
             ViewKind::Hot => "Hot",
+            ViewKind::Catalog => "All Skills",
+            ViewKind::Custom { label, .. } => label,
+        }
+    }
+
+    /// Identifier used in skills.sh URLs and cache file names.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            ViewKind::AllTime => "all-time",
+            ViewKind::Trending => "trending",
+            ViewKind::Hot => "hot",
+            ViewKind::Catalog => "catalog",
+            ViewKind::Custom { slug, .. } => slug,
         }
     }
 
+    /// The skills.sh leaderboards.
     pub fn all() -> [ViewKind; 3] {
         [ViewKind::AllTime, ViewKind::Trending, ViewKind::Hot]
     }
+}
 
-    pub fn next(&self) -> ViewKind {
-        match self {
-            ViewKind::AllTime => ViewKind::Trending,
-            ViewKind::Trending => ViewKind::Hot,
-            ViewKind::Hot => ViewKind::AllTime,
-        }
-    }
+/// Typed view of the YAML frontmatter at the top of a SKILL.md file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SkillManifest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub license: Option<String>,
+    pub allowed_tools: Vec<String>,
+    pub version: Option<String>,
+    /// Entries of the `metadata` mapping plus any unrecognised top-level keys.
+    pub metadata: BTreeMap<String, String>,
+}
 
-    pub fn prev(&self) -> ViewKind {
-        match self {
-            ViewKind::AllTime => ViewKind::Hot,
-            ViewKind::Trending => ViewKind::AllTime,
-            ViewKind::Hot => ViewKind::Trending,
-        }
+/// GitHub API quota, read from the `X-RateLimit-*` response headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    pub reset_at: SystemTime,
+}
+
+impl RateLimit {
+    pub fn from_headers(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let header = |name: &str| -> Option<u64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+        Some(Self {
+            limit: header("x-ratelimit-limit")?,
+            remaining: header("x-ratelimit-remaining")?,
+            reset_at: UNIX_EPOCH + Duration::from_secs(header("x-ratelimit-reset")?),
+        })
     }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct SkillDetail {
+    /// The SKILL.md document exactly as fetched.
     pub markdown: String,
+    pub manifest: Option<SkillManifest>,
+    /// The markdown with the frontmatter block removed.
+    pub body: String,
+    /// Branch, tag or commit the document was fetched from.
+    pub git_ref: Option<String>,
+}
+
+impl SkillDetail {
+    pub fn from_markdown(markdown: String) -> Self {
+        let (frontmatter, body) = parse::split_frontmatter(&markdown);
+        let manifest = frontmatter.map(parse::parse_manifest);
+        let body = body.to_string();
+        Self {
+            markdown,
+            manifest,
+            body,
+            git_ref: None,
+        }
+    }
+}
+
+/// One file of a skill's folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillFile {
+    /// Path relative to the skill's folder, `/`-separated.
+    pub path: String,
+    pub contents: Vec<u8>,
+    /// Whether the file is a script to be marked executable.
+    pub executable: bool,
+}
+
+/// Everything a skill's folder holds: its SKILL.md plus any scripts,
+/// references and assets next to it.
+#[derive(Debug, Clone, Default)]
+pub struct SkillFiles {
+    /// Branch, tag or commit the files were read at.
+    pub git_ref: Option<String>,
+    /// Commit `git_ref` pointed at, where the registry knows it.
+    pub commit: Option<String>,
+    pub files: Vec<SkillFile>,
 }
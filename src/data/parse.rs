@@ -1,6 +1,9 @@
-use crate::data::SkillSummary;
+use crate::data::{flight, SkillManifest, SkillSummary};
 use crate::error::{AppError, Result};
+use scraper::{Html, Selector};
 use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -13,6 +16,21 @@ struct RawSkill {
     installs_yesterday: Option<i64>,
     #[serde(default)]
     change: Option<i64>,
+    #[serde(flatten)]
+    extra: BTreeMap<String, Value>,
+}
+
+/// Fields every skill entry is expected to carry, and fields only some views
+/// send. Anything else is reported as drift and kept in `extra`.
+const REQUIRED_FIELDS: [&str; 4] = ["source", "skillId", "name", "installs"];
+const OPTIONAL_FIELDS: [&str; 2] = ["installsYesterday", "change"];
+
+/// Skills extracted from a skills.sh page, with any signs that the page's
+/// data no longer matches what this version expects.
+#[derive(Debug, Clone, Default)]
+pub struct Leaderboard {
+    pub skills: Vec<SkillSummary>,
+    pub warnings: Vec<String>,
 }
 
 impl From<RawSkill> for SkillSummary {
@@ -24,118 +42,469 @@ impl From<RawSkill> for SkillSummary {
             installs: raw.installs,
             installs_yesterday: raw.installs_yesterday,
             change: raw.change,
+            extra: raw.extra,
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct SkillsWrapper {
-    skills: Vec<RawSkill>,
+/// Extract the leaderboard from a skills.sh page.
+///
+/// The embedded Next.js flight data is preferred; when it is missing or does
+/// not contain a skills list, the rendered leaderboard markup is scraped
+/// instead so a front-end refactor does not leave the list empty.
+pub fn extract_skills_from_html(html: &str) -> Result<Vec<SkillSummary>> {
+    extract_leaderboard(html).map(|leaderboard| leaderboard.skills)
 }
 
-pub fn extract_skills_from_html(html: &str) -> Result<Vec<SkillSummary>> {
-    let unescaped = html.replace(r#"\""#, "\"");
+/// Like [`extract_skills_from_html`], also reporting schema drift: missing or
+/// unknown skill fields, or a fallback to the page markup.
+pub fn extract_leaderboard(html: &str) -> Result<Leaderboard> {
+    match extract_skills_from_flight(html) {
+        Ok(leaderboard) => Ok(leaderboard),
+        Err(e) => match extract_skills_from_dom(html) {
+            Some(skills) => Ok(Leaderboard {
+                skills,
+                warnings: vec![format!("{}; read the page markup instead", e)],
+            }),
+            None => Err(e),
+        },
+    }
+}
+
+/// Decode the page's flight payload and locate the skills list as the
+/// largest JSON array whose objects carry `source` and `skillId` fields.
+pub fn extract_skills_from_flight(html: &str) -> Result<Leaderboard> {
+    let payload = flight::payload(html).ok_or_else(|| {
+        AppError::SchemaChanged("no Next.js flight data in the skills.sh page".to_string())
+    })?;
+
+    let rows = flight::json_rows(&payload);
+    let mut candidates = Vec::new();
+    for row in &rows {
+        flight::find_object_arrays(row, &["source", "skillId"], &mut candidates);
+    }
+
+    let items = candidates
+        .into_iter()
+        .max_by_key(|items| items.len())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let skills: Vec<SkillSummary> = items
+        .iter()
+        .filter_map(|item| serde_json::from_value::<RawSkill>(item.clone()).ok())
+        .map(Into::into)
+        .collect();
+
+    if skills.is_empty() {
+        return Err(AppError::SchemaChanged(
+            "could not find the skills list in the skills.sh page".to_string(),
+        ));
+    }
+    Ok(Leaderboard {
+        skills,
+        warnings: detect_drift(items),
+    })
+}
 
-    if let Some(skills) = try_parse_wrapper_json(&unescaped) {
-        if !skills.is_empty() {
-            return Ok(skills);
+/// Compare skill entries against the fields `RawSkill` knows about.
+pub fn detect_drift(items: &[Value]) -> Vec<String> {
+    let mut missing: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut unknown: BTreeMap<&str, usize> = BTreeMap::new();
+
+    for item in items {
+        let Some(obj) = item.as_object() else {
+            continue;
+        };
+        for field in REQUIRED_FIELDS {
+            if !obj.contains_key(field) {
+                *missing.entry(field).or_default() += 1;
+            }
+        }
+        for key in obj.keys() {
+            if !REQUIRED_FIELDS.contains(&key.as_str()) && !OPTIONAL_FIELDS.contains(&key.as_str())
+            {
+                *unknown.entry(key.as_str()).or_default() += 1;
+            }
         }
     }
 
-    if let Some(skills) = try_parse_wrapper_json(html) {
-        if !skills.is_empty() {
-            return Ok(skills);
+    let mut warnings = Vec::new();
+    for (field, count) in missing {
+        warnings.push(format!(
+            "field `{}` missing from {} of {} skills",
+            field,
+            count,
+            items.len()
+        ));
+    }
+    if !unknown.is_empty() {
+        let fields: Vec<String> = unknown.keys().map(|k| format!("`{}`", k)).collect();
+        warnings.push(format!("new skill fields: {}", fields.join(", ")));
+    }
+    warnings
+}
+
+/// Scrape the rendered leaderboard: every link to `/{owner}/{repo}/{skill}`
+/// is a row, its first heading is the name and its last numeric text is the
+/// install count.
+pub fn extract_skills_from_dom(html: &str) -> Option<Vec<SkillSummary>> {
+    let document = Html::parse_document(html);
+    let links = Selector::parse("a[href]").ok()?;
+    let heading = Selector::parse("h1, h2, h3, h4, h5, h6").ok()?;
+
+    let mut skills: Vec<SkillSummary> = Vec::new();
+    for link in document.select(&links) {
+        let Some((source, skill_id)) = link.value().attr("href").and_then(skill_path) else {
+            continue;
+        };
+        if skills
+            .iter()
+            .any(|s| s.source == source && s.skill_id == skill_id)
+        {
+            continue;
         }
+
+        let name = link
+            .select(&heading)
+            .next()
+            .map(|h| h.text().collect::<String>().trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| skill_id.clone());
+        let installs = link
+            .text()
+            .map(str::trim)
+            .filter_map(parse_count)
+            .last()
+            .unwrap_or(0);
+
+        skills.push(SkillSummary {
+            source,
+            skill_id,
+            name,
+            installs,
+            installs_yesterday: None,
+            change: None,
+            extra: BTreeMap::new(),
+        });
     }
 
-    if let Some(skills) = try_parse_skills_array(&unescaped) {
-        if !skills.is_empty() {
-            return Ok(skills);
+    (!skills.is_empty()).then_some(skills)
+}
+
+/// Split a leaderboard href such as `/owner/repo/skill` (or the same path on
+/// `https://skills.sh`) into its source and skill id.
+fn skill_path(href: &str) -> Option<(String, String)> {
+    let path = href
+        .strip_prefix("https://skills.sh")
+        .unwrap_or(href)
+        .strip_prefix('/')?;
+    if path.contains(['?', '#', ':']) {
+        return None;
+    }
+    let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    match segments.as_slice() {
+        [owner, repo, skill] if [owner, repo, skill].iter().all(|s| !s.is_empty()) => {
+            Some((format!("{}/{}", owner, repo), skill.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// Parse install counts as rendered on the page: `1234`, `1,234`, `12.3K`
+/// or `1.2M`.
+fn parse_count(text: &str) -> Option<i64> {
+    let text = text.replace(',', "");
+    let (number, multiplier) = match text.chars().last()? {
+        'K' | 'k' => (&text[..text.len() - 1], 1_000.0),
+        'M' | 'm' => (&text[..text.len() - 1], 1_000_000.0),
+        _ => (text.as_str(), 1.0),
+    };
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    let value: f64 = number.parse().ok()?;
+    Some((value * multiplier).round() as i64)
+}
+
+/// Split a SKILL.md document into its YAML frontmatter and markdown body.
+///
+/// The frontmatter must open on the first line with `---` and close with a
+/// line containing only `---` (or `...`). Documents without a frontmatter
+/// block are returned unchanged as the body.
+pub fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let Some(rest) = content
+        .strip_prefix("---\r\n")
+        .or_else(|| content.strip_prefix("---\n"))
+    else {
+        return (None, content);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            let frontmatter = &rest[..offset];
+            let body = &rest[offset + line.len()..];
+            return (Some(frontmatter), body.trim_start_matches(['\r', '\n']));
         }
+        offset += line.len();
     }
 
-    Err(AppError::Parse(
-        "Could not extract skills data from HTML".to_string(),
-    ))
+    (None, content)
 }
 
-fn try_parse_wrapper_json(html: &str) -> Option<Vec<SkillSummary>> {
-    let marker = r#"[{"skills":"#;
-    let start_pos = html.find(marker)?;
+/// Parse the frontmatter of a SKILL.md document into a [`SkillManifest`].
+///
+/// Only the subset of YAML used by skill manifests is supported: scalar
+/// values (plain, single- or double-quoted), block scalars (`|` and `>`),
+/// folded plain continuation lines, flow and block sequences, and one level
+/// of nested mappings. Unknown top-level keys are kept in `metadata`.
+pub fn parse_manifest(frontmatter: &str) -> SkillManifest {
+    let lines: Vec<&str> = frontmatter.lines().collect();
+    let mut manifest = SkillManifest::default();
+    let mut i = 0;
 
-    let search_area = &html[start_pos..];
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
 
-    let mut depth = 0;
-    let mut end_idx = 0;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') || indent_of(line) > 0 {
+            continue;
+        }
+        let Some((key, value)) = split_key_value(line) else {
+            continue;
+        };
+
+        // Collect the indented lines that belong to this key.
+        let start = i;
+        while i < lines.len() && (lines[i].trim().is_empty() || indent_of(lines[i]) > 0) {
+            i += 1;
+        }
+        let nested = &lines[start..i];
 
-    for (i, c) in search_area.char_indices() {
-        match c {
-            '[' => depth += 1,
-            ']' => {
-                depth -= 1;
-                if depth == 0 {
-                    end_idx = i + 1;
-                    break;
-                }
+        match key {
+            "name" => manifest.name = Some(parse_scalar(value, nested)),
+            "description" => manifest.description = Some(parse_scalar(value, nested)),
+            "license" => manifest.license = Some(parse_scalar(value, nested)),
+            "version" => manifest.version = Some(parse_scalar(value, nested)),
+            "allowed-tools" | "allowed_tools" => {
+                manifest.allowed_tools = parse_list(value, nested);
+            }
+            "metadata" if value.is_empty() => {
+                manifest.metadata.extend(parse_mapping(nested));
+            }
+            _ => {
+                let is_list = value.starts_with('[')
+                    || (value.is_empty() && nested.iter().any(|l| is_sequence_item(l)));
+                let value = if is_list {
+                    parse_list(value, nested).join(", ")
+                } else {
+                    parse_scalar(value, nested)
+                };
+                manifest.metadata.insert(key.to_string(), value);
             }
-            _ => {}
         }
     }
 
-    if end_idx == 0 {
+    manifest
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_sequence_item(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed == "-" || trimmed.starts_with("- ")
+}
+
+fn split_key_value(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    let colon = line.find(':')?;
+    let key = line[..colon].trim().trim_matches(['"', '\'']);
+    let value = &line[colon + 1..];
+    if key.is_empty() || !(value.is_empty() || value.starts_with([' ', '\t'])) {
         return None;
     }
+    Some((key, strip_comment(value.trim())))
+}
 
-    let json_str = &search_area[..end_idx];
+/// Drop a trailing ` # comment`. In a quoted scalar only a comment after the
+/// closing quote counts; one that is not closed on this line is kept whole.
+fn strip_comment(value: &str) -> &str {
+    let start = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => match closing_quote(value, quote) {
+            Some(end) => end,
+            None => return value,
+        },
+        _ => 0,
+    };
+    match value[start..].find(" #") {
+        Some(pos) => value[..start + pos].trim_end(),
+        None => value,
+    }
+}
 
-    if let Ok(wrappers) = serde_json::from_str::<Vec<SkillsWrapper>>(json_str) {
-        if let Some(first) = wrappers.first() {
-            return Some(first.skills.iter().cloned().map(Into::into).collect());
+/// Byte offset just past the quote closing the scalar that opens `value`.
+fn closing_quote(value: &str, quote: char) -> Option<usize> {
+    let mut chars = value.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if quote == '"' && c == '\\' {
+            chars.next();
+        } else if c == quote {
+            // '' is an escaped quote inside a single-quoted scalar.
+            if quote == '\'' && chars.next_if(|&(_, next)| next == '\'').is_some() {
+                continue;
+            }
+            return Some(i + 1);
         }
     }
-
     None
 }
 
-fn try_parse_skills_array(html: &str) -> Option<Vec<SkillSummary>> {
-    let marker = r#""source":"#;
-    let start_pos = html.find(marker)?;
+fn parse_scalar(value: &str, nested: &[&str]) -> String {
+    if value.starts_with(['|', '>']) {
+        return parse_block_scalar(value.starts_with('|'), nested);
+    }
 
-    let before = &html[..start_pos];
-    let array_start = before.rfind('[')?;
+    if value.starts_with('"') || value.starts_with('\'') {
+        // Quoted scalars may span lines; join them before unquoting.
+        let mut joined = value.to_string();
+        for line in nested {
+            joined.push(' ');
+            joined.push_str(line.trim());
+        }
+        return unquote(&joined);
+    }
 
-    if !before[array_start..].contains("{") {
-        return None;
+    // Plain scalars fold continuation lines into single spaces.
+    let mut parts: Vec<&str> = Vec::new();
+    if !value.is_empty() {
+        parts.push(value);
     }
+    parts.extend(nested.iter().map(|l| l.trim()).filter(|l| !l.is_empty()));
+    parts.join(" ")
+}
 
-    let search_area = &html[array_start..];
-    let mut depth = 0;
-    let mut end_idx = 0;
+/// Block scalars keep (`|`) or fold (`>`) their lines. Chomping indicators
+/// are accepted but trailing newlines are always dropped, since manifest
+/// values are displayed as single table cells.
+fn parse_block_scalar(literal: bool, nested: &[&str]) -> String {
+    let indent = nested
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| indent_of(l))
+        .min()
+        .unwrap_or(0);
+    let lines: Vec<&str> = nested
+        .iter()
+        .map(|l| l.get(indent..).unwrap_or(""))
+        .collect();
 
-    for (i, c) in search_area.char_indices() {
-        match c {
-            '[' => depth += 1,
-            ']' => {
-                depth -= 1;
-                if depth == 0 {
-                    end_idx = i + 1;
-                    break;
-                }
+    let text = if literal {
+        lines.join("\n")
+    } else {
+        let mut folded = String::new();
+        for line in &lines {
+            if line.is_empty() {
+                folded.push('\n');
+                continue;
+            }
+            if !folded.is_empty() && !folded.ends_with('\n') {
+                folded.push(' ');
             }
-            _ => {}
+            folded.push_str(line);
         }
-    }
+        folded
+    };
 
-    if end_idx == 0 {
-        return None;
+    text.trim_end_matches('\n').to_string()
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    if let Some(inner) = value.strip_prefix('\'') {
+        let inner = inner.strip_suffix('\'').unwrap_or(inner);
+        return inner.replace("''", "'");
+    }
+    if let Some(inner) = value.strip_prefix('"') {
+        let inner = inner.strip_suffix('"').unwrap_or(inner);
+        let mut out = String::with_capacity(inner.len());
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some('r') => out.push('\r'),
+                Some('0') => out.push('\0'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        }
+        return out;
     }
+    value.to_string()
+}
 
-    let json_str = &search_area[..end_idx];
+fn parse_list(value: &str, nested: &[&str]) -> Vec<String> {
+    if let Some(inner) = value.strip_prefix('[') {
+        let inner = inner.strip_suffix(']').unwrap_or(inner);
+        return inner
+            .split(',')
+            .map(unquote)
+            .filter(|item| !item.is_empty())
+            .collect();
+    }
 
-    if let Ok(skills) = serde_json::from_str::<Vec<RawSkill>>(json_str) {
-        return Some(skills.into_iter().map(Into::into).collect());
+    if value.is_empty() {
+        return nested
+            .iter()
+            .filter(|l| is_sequence_item(l))
+            .map(|l| unquote(strip_comment(l.trim_start().trim_start_matches('-').trim())))
+            .filter(|item| !item.is_empty())
+            .collect();
     }
 
-    None
+    // A single scalar such as `Read, Grep, Bash(git:*)` or `Read Grep`.
+    let scalar = parse_scalar(value, nested);
+    let separator = if scalar.contains(',') { ',' } else { ' ' };
+    scalar
+        .split(separator)
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn parse_mapping(nested: &[&str]) -> Vec<(String, String)> {
+    let indent = nested
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| indent_of(l))
+        .min()
+        .unwrap_or(0);
+
+    let mut entries = Vec::new();
+    let mut i = 0;
+    while i < nested.len() {
+        let line = nested[i];
+        i += 1;
+        if line.trim().is_empty() || indent_of(line) != indent {
+            continue;
+        }
+        let Some((key, value)) = split_key_value(line) else {
+            continue;
+        };
+        let start = i;
+        while i < nested.len() && (nested[i].trim().is_empty() || indent_of(nested[i]) > indent) {
+            i += 1;
+        }
+        entries.push((key.to_string(), parse_scalar(value, &nested[start..i])));
+    }
+    entries
 }
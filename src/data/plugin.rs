@@ -0,0 +1,356 @@
+//! Claude plugin marketplaces: `.claude-plugin/marketplace.json` lists the
+//! plugins a repository distributes, and each plugin's
+//! `.claude-plugin/plugin.json` describes it. Skills bundled by a plugin live
+//! in the folders its manifests declare (`skills/` by default).
+
+use crate::data::tree::SkillIndex;
+use crate::data::{SkillDetail, SkillManifest};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+pub const MARKETPLACE_PATH: &str = ".claude-plugin/marketplace.json";
+pub const PLUGIN_MANIFEST_PATH: &str = ".claude-plugin/plugin.json";
+
+/// Plugins are listed next to skills under keys with this `skill_id` prefix,
+/// so a plugin and a skill sharing a name stay distinct.
+pub const PLUGIN_KEY_PREFIX: &str = "plugin:";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarketplaceManifest {
+    pub name: String,
+    #[serde(default)]
+    pub owner: Option<Author>,
+    #[serde(default)]
+    pub plugins: Vec<MarketplaceEntry>,
+}
+
+/// A plugin as listed in `marketplace.json`. Entries may carry any
+/// `plugin.json` field inline, overriding the plugin's own manifest.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarketplaceEntry {
+    pub name: String,
+    pub source: PluginSource,
+    #[serde(flatten)]
+    pub manifest: PluginManifest,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum PluginSource {
+    /// A path inside the marketplace repository, such as `./plugins/pdf`.
+    Path(String),
+    /// A plugin hosted elsewhere, e.g. `{"source": "github", "repo": "o/r"}`.
+    Remote {
+        source: String,
+        #[serde(default)]
+        repo: Option<String>,
+        #[serde(default)]
+        url: Option<String>,
+    },
+}
+
+/// Fields of `plugin.json`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PluginManifest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub author: Option<Author>,
+    #[serde(default)]
+    pub homepage: Option<String>,
+    #[serde(default)]
+    pub repository: Option<String>,
+    #[serde(default)]
+    pub license: Option<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Skill folders, or folders of skill folders, relative to the plugin.
+    #[serde(default)]
+    pub skills: Option<Paths>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Author {
+    Name(String),
+    Details {
+        name: String,
+        #[serde(default)]
+        email: Option<String>,
+    },
+}
+
+impl std::fmt::Display for Author {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Author::Name(name) | Author::Details { name, email: None } => write!(f, "{}", name),
+            Author::Details {
+                name,
+                email: Some(email),
+            } => write!(f, "{} <{}>", name, email),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Paths {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Paths {
+    fn to_vec(&self) -> Vec<String> {
+        match self {
+            Paths::One(path) => vec![path.clone()],
+            Paths::Many(paths) => paths.clone(),
+        }
+    }
+}
+
+/// A repository's marketplace with every plugin resolved against its tree.
+#[derive(Debug, Clone)]
+pub struct Marketplace {
+    pub name: String,
+    pub owner: Option<String>,
+    pub plugins: Vec<Plugin>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Plugin {
+    pub name: String,
+    pub manifest: PluginManifest,
+    /// Plugin folder inside the repository ("" for the root), or `None` for
+    /// plugins hosted elsewhere.
+    pub root: Option<String>,
+    /// Where a remotely hosted plugin lives.
+    pub remote: Option<String>,
+    pub skills: Vec<PluginSkill>,
+}
+
+/// A skill bundled by a plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginSkill {
+    /// Folder name, which is also the skill id.
+    pub folder: String,
+    /// Path of the SKILL.md inside the repository.
+    pub path: String,
+}
+
+impl MarketplaceEntry {
+    /// Repository-relative plugin folder, for plugins stored in the
+    /// marketplace repository.
+    pub fn root(&self) -> Option<String> {
+        match &self.source {
+            PluginSource::Path(path) => normalize(path),
+            PluginSource::Remote { .. } => None,
+        }
+    }
+}
+
+impl Marketplace {
+    /// A marketplace with `plugin.json` at the repository root as its only
+    /// plugin, for repositories that ship a single plugin.
+    pub fn single(repo_name: &str, manifest: PluginManifest, index: &SkillIndex) -> Self {
+        let name = manifest
+            .name
+            .clone()
+            .unwrap_or_else(|| repo_name.to_string());
+        let entry = MarketplaceEntry {
+            name: name.clone(),
+            source: PluginSource::Path("./".to_string()),
+            manifest: PluginManifest::default(),
+        };
+        Self {
+            name,
+            owner: None,
+            plugins: vec![resolve(&entry, Some(&manifest), index)],
+        }
+    }
+
+    /// Resolve each entry of `manifest`, using the fetched `plugin.json`
+    /// manifests keyed by plugin root.
+    pub fn resolve(
+        manifest: MarketplaceManifest,
+        plugin_manifests: &HashMap<String, PluginManifest>,
+        index: &SkillIndex,
+    ) -> Self {
+        let plugins = manifest
+            .plugins
+            .iter()
+            .map(|entry| {
+                let own = entry.root().and_then(|root| plugin_manifests.get(&root));
+                resolve(entry, own, index)
+            })
+            .collect();
+        Self {
+            name: manifest.name,
+            owner: manifest.owner.map(|owner| owner.to_string()),
+            plugins,
+        }
+    }
+}
+
+fn resolve(entry: &MarketplaceEntry, own: Option<&PluginManifest>, index: &SkillIndex) -> Plugin {
+    let manifest = merge(&entry.manifest, own);
+    let root = entry.root();
+    let remote = match &entry.source {
+        PluginSource::Path(_) => None,
+        PluginSource::Remote { source, repo, url } => Some(
+            repo.clone()
+                .or_else(|| url.clone())
+                .unwrap_or_else(|| source.clone()),
+        ),
+    };
+
+    let mut skills = Vec::new();
+    if let Some(root) = &root {
+        let dirs = manifest
+            .skills
+            .as_ref()
+            .map(Paths::to_vec)
+            .unwrap_or_else(|| vec!["skills".to_string()]);
+        for dir in dirs.iter().filter_map(|dir| normalize(dir)) {
+            let dir = join(root, &dir);
+            for entry in &index.entries {
+                let entry_dir = entry.dir();
+                let inside = dir.is_empty()
+                    || entry_dir == dir
+                    || entry_dir
+                        .strip_prefix(dir.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'));
+                if inside && !skills.iter().any(|s: &PluginSkill| s.path == entry.path) {
+                    skills.push(PluginSkill {
+                        folder: entry.folder.clone(),
+                        path: entry.path.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    Plugin {
+        name: entry.name.clone(),
+        manifest,
+        root,
+        remote,
+        skills,
+    }
+}
+
+/// Marketplace entry fields take precedence over the plugin's own manifest.
+fn merge(entry: &PluginManifest, own: Option<&PluginManifest>) -> PluginManifest {
+    let Some(own) = own else {
+        return entry.clone();
+    };
+    PluginManifest {
+        name: entry.name.clone().or_else(|| own.name.clone()),
+        description: entry
+            .description
+            .clone()
+            .or_else(|| own.description.clone()),
+        version: entry.version.clone().or_else(|| own.version.clone()),
+        author: entry.author.clone().or_else(|| own.author.clone()),
+        homepage: entry.homepage.clone().or_else(|| own.homepage.clone()),
+        repository: entry.repository.clone().or_else(|| own.repository.clone()),
+        license: entry.license.clone().or_else(|| own.license.clone()),
+        keywords: if entry.keywords.is_empty() {
+            own.keywords.clone()
+        } else {
+            entry.keywords.clone()
+        },
+        skills: entry.skills.clone().or_else(|| own.skills.clone()),
+    }
+}
+
+/// `./plugins/pdf/` → `plugins/pdf`; `./` → ``. Paths leaving the
+/// repository are rejected.
+fn normalize(path: &str) -> Option<String> {
+    let parts: Vec<&str> = path
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect();
+    if parts.contains(&"..") {
+        return None;
+    }
+    Some(parts.join("/"))
+}
+
+fn join(root: &str, path: &str) -> String {
+    match (root.is_empty(), path.is_empty()) {
+        (true, _) => path.to_string(),
+        (_, true) => root.to_string(),
+        _ => format!("{}/{}", root, path),
+    }
+}
+
+impl Plugin {
+    /// The plugin-level detail page: its manifest as the metadata table and
+    /// the bundled skills as the body.
+    pub fn detail(&self, marketplace: &Marketplace, source: &str) -> SkillDetail {
+        let manifest = &self.manifest;
+        let mut metadata = BTreeMap::new();
+        if let Some(author) = &manifest.author {
+            metadata.insert("author".to_string(), author.to_string());
+        }
+        for (key, value) in [
+            ("homepage", &manifest.homepage),
+            ("repository", &manifest.repository),
+        ] {
+            if let Some(value) = value {
+                metadata.insert(key.to_string(), value.clone());
+            }
+        }
+        if !manifest.keywords.is_empty() {
+            metadata.insert("keywords".to_string(), manifest.keywords.join(", "));
+        }
+        metadata.insert("marketplace".to_string(), marketplace.name.clone());
+
+        let mut body = format!("# {}\n\n", self.name);
+        if let Some(description) = &manifest.description {
+            let _ = writeln!(body, "{}\n", description);
+        }
+        match (&self.root, &self.remote) {
+            (_, Some(remote)) => {
+                let _ = writeln!(body, "Hosted in `{}`.\n", remote);
+            }
+            (Some(root), None) if !root.is_empty() => {
+                let _ = writeln!(body, "Located at `{}`.\n", root);
+            }
+            _ => {}
+        }
+
+        body.push_str("## Skills\n\n");
+        if self.skills.is_empty() {
+            body.push_str("This plugin bundles no skills.\n");
+        }
+        for skill in &self.skills {
+            let _ = writeln!(body, "- **{}** — `{}`", skill.folder, skill.path);
+        }
+
+        let _ = write!(
+            body,
+            "\n## Install\n\nIn Claude Code:\n\n```\n/plugin marketplace add {}\n/plugin install {}@{}\n```\n",
+            source, self.name, marketplace.name
+        );
+
+        SkillDetail {
+            markdown: body.clone(),
+            manifest: Some(SkillManifest {
+                name: Some(self.name.clone()),
+                description: manifest.description.clone(),
+                license: manifest.license.clone(),
+                allowed_tools: Vec::new(),
+                version: manifest.version.clone(),
+                metadata,
+            }),
+            body,
+            git_ref: None,
+        }
+    }
+}
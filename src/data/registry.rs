@@ -0,0 +1,234 @@
+//! Skill catalogs the TUI can browse. Each configured registry gets its own
+//! group of tabs; skills.sh is the default.
+
+use crate::config::{Config, RegistryKind};
+use crate::data::host::{HostKind, Repo};
+use crate::data::{
+    IndexRegistry, Leaderboard, LocalRegistry, RateLimit, SkillDetail, SkillFile, SkillFiles,
+    SkillKey, SkillSummary, SkillsClient, SkillsClientBuilder, ViewKind,
+};
+use crate::error::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// A catalog of skills: where listings come from, where their documents
+/// live, and how they are installed.
+#[async_trait]
+pub trait SkillRegistry: Send + Sync {
+    /// File-name safe identifier, unique among the configured registries.
+    fn id(&self) -> &str;
+
+    /// Name shown in the tab bar.
+    fn name(&self) -> &str;
+
+    /// Views this registry offers, in tab order.
+    fn views(&self) -> Vec<ViewKind>;
+
+    async fn list(&self, view: ViewKind) -> Result<Leaderboard>;
+
+    /// Fetch the SKILL.md of `key`, at `git_ref` where the registry is
+    /// versioned.
+    async fn fetch_detail(&self, key: &SkillKey, git_ref: Option<&str>) -> Result<SkillDetail>;
+
+    /// A previously fetched copy of the document, if one is stored locally.
+    fn cached_detail(&self, _key: &SkillKey) -> Option<SkillDetail> {
+        None
+    }
+
+    /// Every file of `key`'s folder, for installing it without npx. Only
+    /// the SKILL.md, unless the registry can list the rest.
+    async fn skill_files(&self, key: &SkillKey, git_ref: Option<&str>) -> Result<SkillFiles> {
+        let detail = self.fetch_detail(key, git_ref).await?;
+        Ok(SkillFiles {
+            git_ref: detail.git_ref,
+            commit: None,
+            files: vec![SkillFile {
+                path: "SKILL.md".to_string(),
+                contents: detail.markdown.into_bytes(),
+                executable: false,
+            }],
+        })
+    }
+
+    /// Whether `skill` can be installed into a skills directory.
+    fn installable(&self, _skill: &SkillSummary) -> bool {
+        true
+    }
+
+    /// Command that installs `skill`, as program and arguments.
+    fn install_command(&self, skill: &SkillSummary) -> Option<Vec<String>>;
+
+    /// Web page of the repository `skill` comes from, where it has one.
+    fn source_url(&self, _skill: &SkillSummary) -> Option<String> {
+        None
+    }
+
+    /// Short text shown after `skill`'s name in listings.
+    fn list_note(&self, _skill: &SkillSummary) -> Option<String> {
+        None
+    }
+
+    /// Further labelled facts shown on `skill`'s page.
+    fn detail_fields(&self, _skill: &SkillSummary) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    /// Whether listings are worth keeping in the on-disk view cache. Cheap,
+    /// local listings are rescanned instead.
+    fn caches_views(&self) -> bool {
+        true
+    }
+
+    /// Whether the registry describes this machine rather than a catalog.
+    /// Its tabs go without the registry name and are rescanned after
+    /// installs and uninstalls.
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    /// Whether `skill`, listed by any registry, is installed on this
+    /// machine. Only a local registry knows.
+    fn has_installed(&self, _skill: &SkillSummary) -> bool {
+        false
+    }
+
+    /// Remaining API quota, for registries backed by a rate-limited API.
+    fn rate_limit(&self) -> Option<RateLimit> {
+        None
+    }
+}
+
+/// skills.sh, or a mirror serving the same pages, with documents on GitHub.
+pub struct SkillsShRegistry {
+    id: String,
+    name: String,
+    client: SkillsClient,
+}
+
+impl SkillsShRegistry {
+    pub fn new(id: impl Into<String>, name: impl Into<String>, client: SkillsClient) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl SkillRegistry for SkillsShRegistry {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn views(&self) -> Vec<ViewKind> {
+        ViewKind::all().to_vec()
+    }
+
+    async fn list(&self, view: ViewKind) -> Result<Leaderboard> {
+        let mut leaderboard = self.client.fetch_skills(view).await?;
+        if !leaderboard.warnings.is_empty() {
+            leaderboard.warnings = vec![format!(
+                "data changed shape ({}); rerun with --dump-responses <dir> and report it",
+                leaderboard.warnings.join("; ")
+            )];
+        }
+        Ok(leaderboard)
+    }
+
+    async fn fetch_detail(&self, key: &SkillKey, git_ref: Option<&str>) -> Result<SkillDetail> {
+        self.client.fetch_detail(key, git_ref).await
+    }
+
+    fn cached_detail(&self, key: &SkillKey) -> Option<SkillDetail> {
+        self.client.cached_detail(key)
+    }
+
+    async fn skill_files(&self, key: &SkillKey, git_ref: Option<&str>) -> Result<SkillFiles> {
+        self.client.fetch_skill_files(key, git_ref).await
+    }
+
+    fn install_command(&self, skill: &SkillSummary) -> Option<Vec<String>> {
+        let repo = self.client.hosts().resolve(&skill.source);
+        Some(git_install_command(skill, &repo))
+    }
+
+    fn source_url(&self, skill: &SkillSummary) -> Option<String> {
+        Some(self.client.hosts().resolve(&skill.source).web_url())
+    }
+
+    fn rate_limit(&self) -> Option<RateLimit> {
+        self.client.rate_limit()
+    }
+}
+
+/// `npx skills add` for a skill in `repo`. GitHub and GitLab repositories
+/// are given by their page URL, other hosts by their clone URL.
+pub fn git_install_command(skill: &SkillSummary, repo: &Repo) -> Vec<String> {
+    let url = match repo.kind {
+        HostKind::Github | HostKind::Gitlab => repo.web_url(),
+        HostKind::Gitea | HostKind::Generic => repo.clone_url(),
+    };
+    vec![
+        "npx".to_string(),
+        "skills".to_string(),
+        "add".to_string(),
+        url,
+        "--skill".to_string(),
+        skill.skill_id.clone(),
+    ]
+}
+
+/// Build the registries listed in `config`. `builder` carries the shared
+/// client settings; each registry may override its base URL.
+pub fn from_config(
+    config: &Config,
+    builder: &SkillsClientBuilder,
+) -> Result<Vec<Arc<dyn SkillRegistry>>> {
+    config
+        .registries()
+        .into_iter()
+        .map(|registry| -> Result<Arc<dyn SkillRegistry>> {
+            match registry.kind {
+                RegistryKind::SkillsSh => {
+                    let mut builder = builder.clone().fetch_mode(registry.fetch);
+                    if let Some(url) = &registry.url {
+                        builder = builder.registry_url(url);
+                    }
+                    Ok(Arc::new(SkillsShRegistry::new(
+                        registry.id(),
+                        registry.name,
+                        builder.build()?,
+                    )))
+                }
+                RegistryKind::Index => {
+                    // Config validation guarantees a location for index registries.
+                    let location = registry
+                        .url
+                        .clone()
+                        .or_else(|| registry.path.as_ref().map(|p| p.display().to_string()))
+                        .unwrap_or_default();
+                    Ok(Arc::new(IndexRegistry::new(
+                        registry.id(),
+                        registry.name,
+                        location,
+                        builder.clone().build()?,
+                    )))
+                }
+                RegistryKind::Local => {
+                    // Config validation guarantees a path for local registries.
+                    let path = registry.path.clone().unwrap_or_default();
+                    Ok(Arc::new(LocalRegistry::new(
+                        registry.id(),
+                        registry.name,
+                        path,
+                    )))
+                }
+            }
+        })
+        .collect()
+}
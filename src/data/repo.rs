@@ -0,0 +1,304 @@
+//! Browsing every skill in a single git repository, for skills that are not
+//! on any leaderboard.
+
+use crate::data::host::{self, GITHUB_DOMAIN};
+use crate::data::plugin::{Marketplace, PLUGIN_KEY_PREFIX};
+use crate::data::registry::{git_install_command, SkillRegistry};
+use crate::data::{
+    Leaderboard, RateLimit, SkillDetail, SkillFiles, SkillKey, SkillSummary, SkillsClient, ViewKind,
+};
+use crate::error::{AppError, Result};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// Plugins declared by the repository's plugin marketplace.
+pub const PLUGINS_VIEW: ViewKind = ViewKind::Custom {
+    slug: "plugins",
+    label: "Plugins",
+};
+
+/// A repository, optionally pinned to a ref, as typed by the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoReference {
+    /// `owner/repo` on GitHub, `<domain>/<path>` elsewhere.
+    pub source: String,
+    pub git_ref: Option<String>,
+}
+
+impl RepoReference {
+    /// Parse `owner/repo` or a repository URL, with or without its scheme.
+    /// Branch links pin their ref: `…/tree/<ref>` on GitHub, `…/-/tree/<ref>`
+    /// on GitLab and `…/src/branch/<ref>` on Gitea and Forgejo. A link only
+    /// shows where a ref containing `/` ends, so such refs are pinned with
+    /// an `@<ref>` suffix instead. `.git` clone URLs are accepted too.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let rest = input
+            .strip_prefix("https://")
+            .or_else(|| input.strip_prefix("http://"))
+            .unwrap_or(input);
+        if rest.contains("://") {
+            return None;
+        }
+
+        let rest = rest.trim_matches('/');
+        let (rest, pinned) = match rest.split_once('@') {
+            Some((_, "")) => return None,
+            Some((repo, git_ref)) => (repo.trim_end_matches('/'), Some(git_ref.to_string())),
+            None => (rest, None),
+        };
+        let (domain, path) = match rest.split_once('/') {
+            Some((first, path)) if first.contains('.') => {
+                let domain = first.to_ascii_lowercase();
+                let domain = domain.strip_prefix("www.").unwrap_or(&domain).to_string();
+                (domain, path)
+            }
+            _ => (GITHUB_DOMAIN.to_string(), rest),
+        };
+        let github = domain == GITHUB_DOMAIN;
+
+        let mut segments = Vec::new();
+        let mut git_ref = None;
+        let mut parts = path.split('/');
+        while let Some(part) = parts.next() {
+            match part {
+                "tree" | "blob" if github && segments.len() == 2 => {
+                    git_ref = Some(parts.next()?.to_string());
+                    break;
+                }
+                "-" if !github && segments.len() >= 2 => {
+                    if !matches!(parts.next(), Some("tree" | "blob")) {
+                        return None;
+                    }
+                    git_ref = Some(parts.next()?.to_string());
+                    break;
+                }
+                "src" if !github && segments.len() == 2 => {
+                    if !matches!(parts.next(), Some("branch" | "tag" | "commit")) {
+                        return None;
+                    }
+                    git_ref = Some(parts.next()?.to_string());
+                    break;
+                }
+                _ => segments.push(part),
+            }
+        }
+
+        if let Some(last) = segments.last_mut() {
+            *last = last.strip_suffix(".git").unwrap_or(last);
+        }
+        let valid_len = if github {
+            segments.len() == 2
+        } else {
+            segments.len() >= 2
+        };
+        if !valid_len || !segments.iter().all(|s| is_name(s)) {
+            return None;
+        }
+
+        if pinned.is_some() && git_ref.is_some() {
+            return None;
+        }
+
+        Some(Self {
+            source: host::source_for(&domain, &segments.join("/")),
+            git_ref: pinned.or(git_ref),
+        })
+    }
+}
+
+fn is_name(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Every SKILL.md in one repository, found through the same git tree index
+/// used to locate leaderboard skills. Skills are keyed by their folder, or
+/// by its path where several folders share a name, and labelled with their
+/// frontmatter name.
+/// The Plugins view lists the plugins of the repository's marketplace, if it
+/// has one.
+pub struct RepoRegistry {
+    id: String,
+    repo: RepoReference,
+    client: SkillsClient,
+    marketplace: Mutex<Option<Marketplace>>,
+}
+
+impl RepoRegistry {
+    pub fn new(repo: RepoReference, client: SkillsClient) -> Self {
+        let mut id = format!("repo-{}", repo.source.replace('/', "-"));
+        if let Some(git_ref) = &repo.git_ref {
+            id.push('@');
+            id.push_str(git_ref);
+        }
+        Self {
+            id,
+            repo,
+            client,
+            marketplace: Mutex::default(),
+        }
+    }
+
+    async fn marketplace(&self) -> Result<Option<Marketplace>> {
+        if let Some(marketplace) = self.marketplace.lock().unwrap().clone() {
+            return Ok(Some(marketplace));
+        }
+        let source = &self.repo.source;
+        let git_ref = self
+            .client
+            .resolve_ref(source, self.repo.git_ref.as_deref())
+            .await?;
+        let marketplace = self.client.fetch_marketplace(source, &git_ref).await?;
+        self.marketplace.lock().unwrap().clone_from(&marketplace);
+        Ok(marketplace)
+    }
+
+    async fn list_plugins(&self) -> Result<Leaderboard> {
+        // Refreshing the list rereads the manifests.
+        *self.marketplace.lock().unwrap() = None;
+        let source = &self.repo.source;
+        let Some(marketplace) = self.marketplace().await? else {
+            return Ok(Leaderboard {
+                skills: Vec::new(),
+                warnings: vec![format!("{} has no plugin marketplace", source)],
+            });
+        };
+        let skills = marketplace
+            .plugins
+            .iter()
+            .map(|plugin| SkillSummary {
+                source: source.clone(),
+                skill_id: format!("{}{}", PLUGIN_KEY_PREFIX, plugin.name),
+                name: plugin.name.clone(),
+                installs: 0,
+                installs_yesterday: None,
+                change: None,
+                extra: BTreeMap::new(),
+            })
+            .collect();
+        Ok(Leaderboard {
+            skills,
+            warnings: Vec::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl SkillRegistry for RepoRegistry {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.repo.source
+    }
+
+    fn views(&self) -> Vec<ViewKind> {
+        vec![ViewKind::Catalog, PLUGINS_VIEW]
+    }
+
+    async fn list(&self, view: ViewKind) -> Result<Leaderboard> {
+        if view == PLUGINS_VIEW {
+            return self.list_plugins().await;
+        }
+
+        let source = &self.repo.source;
+        let git_ref = self
+            .client
+            .resolve_ref(source, self.repo.git_ref.as_deref())
+            .await?;
+        let index = self.client.skill_index(source, &git_ref).await?;
+
+        let names = self.client.skill_names(source, &git_ref).await?;
+
+        let mut folders: HashMap<&str, usize> = HashMap::new();
+        for entry in &index.entries {
+            *folders.entry(entry.folder.as_str()).or_default() += 1;
+        }
+        let mut skills: Vec<SkillSummary> = index
+            .entries
+            .iter()
+            .zip(names)
+            .map(|(entry, name)| {
+                let skill_id = if folders[entry.folder.as_str()] > 1 && !entry.dir().is_empty() {
+                    entry.dir().to_string()
+                } else {
+                    entry.folder.clone()
+                };
+                SkillSummary {
+                    source: source.clone(),
+                    name: name.unwrap_or_else(|| entry.folder.clone()),
+                    skill_id,
+                    installs: 0,
+                    installs_yesterday: None,
+                    change: None,
+                    extra: BTreeMap::new(),
+                }
+            })
+            .collect();
+        skills.sort_by_key(|skill| skill.name.to_lowercase());
+
+        let mut warnings = Vec::new();
+        if index.truncated {
+            warnings.push(format!(
+                "{} is too large to list completely; some skills are missing",
+                source
+            ));
+        }
+        Ok(Leaderboard { skills, warnings })
+    }
+
+    async fn fetch_detail(&self, key: &SkillKey, git_ref: Option<&str>) -> Result<SkillDetail> {
+        if let Some(name) = key.skill_id.strip_prefix(PLUGIN_KEY_PREFIX) {
+            let marketplace = self.marketplace().await?.ok_or_else(|| {
+                AppError::NotFound(format!("plugin marketplace of {}", self.repo.source))
+            })?;
+            let plugin = marketplace
+                .plugins
+                .iter()
+                .find(|plugin| plugin.name == name)
+                .ok_or_else(|| AppError::NotFound(format!("plugin {}", name)))?;
+            return Ok(plugin.detail(&marketplace, &self.repo.source));
+        }
+
+        let git_ref = git_ref.or(self.repo.git_ref.as_deref());
+        self.client.fetch_detail(key, git_ref).await
+    }
+
+    fn cached_detail(&self, key: &SkillKey) -> Option<SkillDetail> {
+        self.client.cached_detail(key)
+    }
+
+    async fn skill_files(&self, key: &SkillKey, git_ref: Option<&str>) -> Result<SkillFiles> {
+        let git_ref = git_ref.or(self.repo.git_ref.as_deref());
+        self.client.fetch_skill_files(key, git_ref).await
+    }
+
+    fn installable(&self, skill: &SkillSummary) -> bool {
+        !skill.skill_id.starts_with(PLUGIN_KEY_PREFIX)
+    }
+
+    fn install_command(&self, skill: &SkillSummary) -> Option<Vec<String>> {
+        // Plugins install from inside Claude Code; their page explains how.
+        if skill.skill_id.starts_with(PLUGIN_KEY_PREFIX) {
+            return None;
+        }
+        let repo = self.client.hosts().resolve(&skill.source);
+        Some(git_install_command(skill, &repo))
+    }
+
+    fn source_url(&self, skill: &SkillSummary) -> Option<String> {
+        Some(self.client.hosts().resolve(&skill.source).web_url())
+    }
+
+    fn caches_views(&self) -> bool {
+        false
+    }
+
+    fn rate_limit(&self) -> Option<RateLimit> {
+        self.client.rate_limit()
+    }
+}
@@ -0,0 +1,185 @@
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+/// Git file mode of a symbolic link.
+pub const SYMLINK_MODE: &str = "120000";
+
+/// One entry of a git trees API response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TreeEntry {
+    pub path: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub sha: String,
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// Git file mode, e.g. `100755` for executables.
+    #[serde(default)]
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TreeResponse {
+    pub sha: String,
+    pub tree: Vec<TreeEntry>,
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// A SKILL.md file found in a repository tree.
+#[derive(Debug, Clone)]
+pub struct SkillEntry {
+    /// Path of the SKILL.md file relative to the repository root.
+    pub path: String,
+    /// Name of the folder containing the SKILL.md file.
+    pub folder: String,
+    /// Blob sha of the SKILL.md file.
+    pub sha: String,
+}
+
+impl SkillEntry {
+    /// Path of the folder containing the SKILL.md file ("" for the root).
+    pub fn dir(&self) -> &str {
+        self.path.rsplit_once('/').map_or("", |(dir, _)| dir)
+    }
+}
+
+/// Every SKILL.md in one repository at one ref, indexed by folder name and,
+/// once a document has been fetched, by its frontmatter name.
+#[derive(Debug, Clone, Default)]
+pub struct SkillIndex {
+    pub entries: Vec<SkillEntry>,
+    pub truncated: bool,
+    /// Path of every file in the tree.
+    files: Vec<String>,
+    executables: HashSet<String>,
+    by_folder: HashMap<String, Vec<usize>>,
+    by_name: HashMap<String, usize>,
+    named: Vec<bool>,
+}
+
+impl SkillIndex {
+    pub fn from_tree(tree: TreeResponse, repo_name: &str) -> Self {
+        let mut index = SkillIndex {
+            truncated: tree.truncated,
+            ..Default::default()
+        };
+
+        for entry in tree.tree {
+            // Symlinks are not installable: their target is outside the
+            // skill, or on the installing machine.
+            if entry.kind != "blob" || entry.mode.as_deref() == Some(SYMLINK_MODE) {
+                continue;
+            }
+            index.files.push(entry.path.clone());
+            if entry.mode.as_deref() == Some("100755") {
+                index.executables.insert(entry.path.clone());
+            }
+            let (dir, file) = entry.path.rsplit_once('/').unwrap_or(("", &entry.path));
+            if !file.eq_ignore_ascii_case("SKILL.md") {
+                continue;
+            }
+            let folder = match dir.rsplit_once('/') {
+                Some((_, folder)) => folder,
+                None if dir.is_empty() => repo_name,
+                None => dir,
+            };
+
+            let idx = index.entries.len();
+            index
+                .by_folder
+                .entry(folder.to_string())
+                .or_default()
+                .push(idx);
+            index.entries.push(SkillEntry {
+                path: entry.path.clone(),
+                folder: folder.to_string(),
+                sha: entry.sha,
+            });
+            index.named.push(false);
+        }
+
+        index
+    }
+
+    /// Entries whose frontmatter name is `skill_id`, then entries whose folder
+    /// is `skill_id`, in the order they should be tried. An id with a `/` is
+    /// the path of a skill folder, as repository catalogs use to tell apart
+    /// folders of the same name, and only that folder is a candidate.
+    pub fn candidates(&self, skill_id: &str) -> Vec<usize> {
+        if skill_id.contains('/') {
+            return self
+                .entries
+                .iter()
+                .position(|entry| entry.dir() == skill_id)
+                .into_iter()
+                .collect();
+        }
+        let named = self.by_name.get(skill_id).copied();
+        let mut out: Vec<usize> = named.into_iter().collect();
+        if let Some(folders) = self.by_folder.get(skill_id) {
+            out.extend(folders.iter().copied().filter(|&idx| Some(idx) != named));
+        }
+        out
+    }
+
+    /// Entries whose frontmatter has not been read yet.
+    pub fn unnamed(&self) -> Vec<usize> {
+        (0..self.entries.len())
+            .filter(|&i| !self.named[i])
+            .collect()
+    }
+
+    /// Files of the skill folder `dir`, leaving out nested folders that hold
+    /// skills of their own. A skill at the repository root (`dir` is "")
+    /// shares it with everything else in the repository, so it only gets its
+    /// SKILL.md and the [`ROOT_SKILL_FOLDERS`].
+    pub fn folder_files(&self, dir: &str) -> Vec<String> {
+        let nested: Vec<&str> = self
+            .entries
+            .iter()
+            .map(SkillEntry::dir)
+            .filter(|&other| other != dir && is_within(other, dir))
+            .collect();
+        self.files
+            .iter()
+            .filter(|path| is_within(path, dir))
+            .filter(|path| !dir.is_empty() || is_root_skill_file(path))
+            .filter(|path| !nested.iter().any(|other| is_within(path, other)))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether the file at `path` has its executable bit set.
+    pub fn is_executable(&self, path: &str) -> bool {
+        self.executables.contains(path)
+    }
+
+    /// Record the frontmatter name of a fetched entry.
+    pub fn record_name(&mut self, idx: usize, name: Option<&str>) {
+        if let Some(named) = self.named.get_mut(idx) {
+            *named = true;
+        }
+        if let Some(name) = name {
+            self.by_name.entry(name.to_string()).or_insert(idx);
+        }
+    }
+}
+
+/// Folders installed along with a SKILL.md at the root of a repository.
+pub const ROOT_SKILL_FOLDERS: [&str; 3] = ["scripts", "references", "assets"];
+
+fn is_root_skill_file(path: &str) -> bool {
+    path.eq_ignore_ascii_case("SKILL.md")
+        || ROOT_SKILL_FOLDERS
+            .iter()
+            .any(|folder| is_within(path, folder))
+}
+
+/// Whether `path` lies inside the folder `dir` ("" for the root).
+fn is_within(path: &str, dir: &str) -> bool {
+    dir.is_empty()
+        || path
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
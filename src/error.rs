@@ -1,15 +1,125 @@
+use std::time::SystemTime;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Network error: {0}")]
-    Network(#[from] reqwest::Error),
+    Network(reqwest::Error),
+
+    #[error("Request timed out: {url}")]
+    Timeout { url: String },
+
+    #[error("HTTP {status} from {url}")]
+    HttpStatus { status: u16, url: String },
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("GitHub API rate limit exceeded, resets in {}", minutes_until(*.reset_at))]
+    RateLimited { reset_at: SystemTime },
+
+    #[error("Repository listing of {0} was truncated")]
+    TreeTruncated(String),
+
+    #[error("Unexpected response format: {0}")]
+    SchemaChanged(String),
+
+    #[error("Installation failed: {0}")]
+    InstallFailed(String),
+
+    #[error("Cannot install: {0}")]
+    InvalidSkill(String),
 
     #[error("Parse error: {0}")]
     Parse(String),
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+
+    #[error("No API known for git host {0}")]
+    UnsupportedHost(String),
+
+    #[error("Git error: {0}")]
+    Git(String),
+}
+
+impl AppError {
+    /// A short suggestion for how the user can recover, if there is one.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            AppError::Network(_) => Some("Check your internet connection and press r to retry"),
+            AppError::Timeout { .. } => Some("The server is slow to respond; press r to retry"),
+            AppError::HttpStatus { status, .. } if *status >= 500 => {
+                Some("The server is having trouble; try again later")
+            }
+            AppError::HttpStatus { .. } => None,
+            AppError::NotFound(_) => Some("The repository or skill may have moved or been deleted"),
+            AppError::RateLimited { .. } => {
+                Some("Set GITHUB_TOKEN or GH_TOKEN to raise the limit to 5000 requests per hour")
+            }
+            AppError::TreeTruncated(_) => Some(
+                "The repository is too large to list through the API; set fetch = \"git\" on its registry",
+            ),
+            AppError::SchemaChanged(_) => {
+                Some("skills.sh changed its page format; please report this issue")
+            }
+            AppError::InstallFailed(_) => Some(
+                "Check that the skills directory is writable and has space, then retry; npx installs also need Node.js on your PATH",
+            ),
+            AppError::Config(_) => Some("Fix the config file or remove it to use the defaults"),
+            AppError::Git(_) => Some("Check that git is installed and can reach the repository"),
+            AppError::UnsupportedHost(_) => {
+                Some("Declare the host's kind in a [[hosts]] section of the config file")
+            }
+            AppError::Parse(_) | AppError::Io(_) | AppError::InvalidSkill(_) => None,
+        }
+    }
+
+    /// Whether the request may succeed if retried later: the network or the
+    /// server failed, or the rate limit ran out. A missing skill is final.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            AppError::Network(_) | AppError::Timeout { .. } | AppError::RateLimited { .. } => true,
+            AppError::HttpStatus { status, .. } => *status >= 500 || *status == 429,
+            _ => false,
+        }
+    }
+
+    /// Map a non-success HTTP status to [`AppError::NotFound`] for 404s and
+    /// [`AppError::HttpStatus`] otherwise. `what` names the missing resource.
+    pub fn from_status(status: reqwest::StatusCode, url: &str, what: impl Into<String>) -> Self {
+        if status == reqwest::StatusCode::NOT_FOUND {
+            AppError::NotFound(what.into())
+        } else {
+            AppError::HttpStatus {
+                status: status.as_u16(),
+                url: url.to_string(),
+            }
+        }
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            AppError::Timeout {
+                url: e.url().map(|u| u.to_string()).unwrap_or_default(),
+            }
+        } else {
+            AppError::Network(e)
+        }
+    }
+}
+
+fn minutes_until(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::now())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format!("{} min", secs.div_ceil(60))
 }
 
 pub type Result<T> = std::result::Result<T, AppError>;
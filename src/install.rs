@@ -0,0 +1,715 @@
+//! Installing skills without Node. A skill's folder is written to a staging
+//! directory beside its destination and renamed into place, so a failed
+//! install never leaves a half-written skill behind.
+//!
+//! Where it goes is an [`InstallTarget`]: one agent's skills directory,
+//! either global or inside the current project. Each installed folder gets a
+//! [`Receipt`] recording where it came from. Uninstalling or updating moves
+//! the old folder to a trash directory instead of deleting it.
+
+use crate::config::{InstallConfig, InstallMethod};
+use crate::data::{parse, SkillFile, SkillFiles, SkillKey, SkillManifest, SkillSummary};
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Agents skillsm knows the skills directories of: name, global directory
+/// and project directory.
+const BUILTIN_AGENTS: [(&str, &str, &str); 3] = [
+    ("claude", "~/.claude/skills", ".claude/skills"),
+    ("codex", "~/.codex/skills", ".codex/skills"),
+    ("opencode", "~/.config/opencode/skills", ".opencode/skills"),
+];
+
+/// Name of the receipt written into every folder skillsm installs.
+pub const RECEIPT_FILE: &str = ".skillsm.json";
+
+/// How the `i` key installs skills.
+#[derive(Debug, Clone)]
+pub enum Installer {
+    /// skillsm writes the skill's folder into one of `targets`.
+    Native { targets: Vec<InstallTarget> },
+    /// The registry's install command is run in the terminal.
+    Npx,
+}
+
+impl Default for Installer {
+    /// The built-in global targets, or none without a home directory.
+    fn default() -> Self {
+        Installer::Native {
+            targets: targets(&builtin_agents(), None).0,
+        }
+    }
+}
+
+impl Installer {
+    /// The installer `config` asks for, with project targets inside
+    /// `project`. Targets [`targets`] warns about are left out.
+    pub fn from_config(config: &InstallConfig, project: Option<&Path>) -> Self {
+        match config.method {
+            InstallMethod::Native => Installer::Native {
+                targets: targets(&agents(config), project).0,
+            },
+            InstallMethod::Npx => Installer::Npx,
+        }
+    }
+}
+
+/// Whether a skill is available in every project or only the current one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Global,
+    Project,
+}
+
+impl Scope {
+    pub fn label(self) -> &'static str {
+        match self {
+            Scope::Global => "global",
+            Scope::Project => "project",
+        }
+    }
+}
+
+/// One agent's skills directory in one scope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallTarget {
+    pub agent: String,
+    pub scope: Scope,
+    pub dir: PathBuf,
+}
+
+impl InstallTarget {
+    /// Identifier remembered between sessions, e.g. `claude:project`.
+    pub fn id(&self) -> String {
+        format!("{}:{}", self.agent, self.scope.label())
+    }
+}
+
+/// Where an agent looks for skills. The global directory may start with
+/// `~`; the project directory is relative to the project root.
+#[derive(Debug, Clone)]
+pub struct AgentDirs {
+    pub name: String,
+    pub global: Option<PathBuf>,
+    pub project: Option<PathBuf>,
+}
+
+/// The built-in agents, overridden or extended by those in `config`.
+pub fn agents(config: &InstallConfig) -> Vec<AgentDirs> {
+    let mut agents = builtin_agents();
+    for agent in &config.agents {
+        let dirs = AgentDirs {
+            name: agent.name.trim().to_string(),
+            global: agent.global.clone(),
+            project: agent.project.clone(),
+        };
+        match agents.iter_mut().find(|a| a.name == dirs.name) {
+            Some(existing) => *existing = dirs,
+            None => agents.push(dirs),
+        }
+    }
+    agents
+}
+
+pub fn builtin_agents() -> Vec<AgentDirs> {
+    BUILTIN_AGENTS
+        .iter()
+        .map(|(name, global, project)| AgentDirs {
+            name: name.to_string(),
+            global: Some(PathBuf::from(global)),
+            project: Some(PathBuf::from(project)),
+        })
+        .collect()
+}
+
+/// The global targets of `agents`, then their project targets inside
+/// `project`. A directory reachable both ways, as when running from the
+/// home directory, is listed once. Global directories under `~` are left
+/// out without a home directory, with a warning each.
+pub fn targets(agents: &[AgentDirs], project: Option<&Path>) -> (Vec<InstallTarget>, Vec<String>) {
+    let mut global = Vec::new();
+    let mut warnings = Vec::new();
+    for agent in agents {
+        let Some(dir) = &agent.global else {
+            continue;
+        };
+        match expand_home(dir) {
+            Ok(dir) => global.push(InstallTarget {
+                agent: agent.name.clone(),
+                scope: Scope::Global,
+                dir,
+            }),
+            Err(e) => warnings.push(format!("{}:global skipped: {}", agent.name, e)),
+        }
+    }
+    let local = agents.iter().filter_map(|agent| {
+        Some(InstallTarget {
+            agent: agent.name.clone(),
+            scope: Scope::Project,
+            dir: project?.join(agent.project.as_ref()?),
+        })
+    });
+
+    let mut targets: Vec<InstallTarget> = Vec::new();
+    for target in global.into_iter().chain(local) {
+        if !targets.iter().any(|t| t.dir == target.dir) {
+            targets.push(target);
+        }
+    }
+    (targets, warnings)
+}
+
+/// Root of the project in `dir`: the enclosing git work tree, or `dir`
+/// itself outside one.
+pub fn project_root(dir: &Path) -> PathBuf {
+    dir.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
+/// The target last chosen in each project, by project root.
+#[derive(Debug, Clone)]
+pub struct TargetMemory {
+    path: PathBuf,
+}
+
+impl TargetMemory {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Id of the target last used in `project`.
+    pub fn get(&self, project: &Path) -> Option<String> {
+        self.load().remove(&project.display().to_string())
+    }
+
+    pub fn remember(&self, project: &Path, target_id: &str) -> Result<()> {
+        let mut choices = self.load();
+        choices.insert(project.display().to_string(), target_id.to_string());
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(&choices)
+            .map_err(|e| AppError::Parse(format!("install targets: {}", e)))?;
+        std::fs::write(&self.path, json)?;
+        Ok(())
+    }
+
+    /// Choices saved so far; an unreadable file counts as none.
+    fn load(&self) -> BTreeMap<String, String> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Where an installed skill came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Receipt {
+    /// Id of the registry it was installed from.
+    pub registry: String,
+    pub source: String,
+    pub skill_id: String,
+    #[serde(default)]
+    pub git_ref: Option<String>,
+    /// Commit `git_ref` pointed at when installed.
+    #[serde(default)]
+    pub commit: Option<String>,
+    /// [`content_hash`] of the files as installed, to tell local edits
+    /// apart. Missing from receipts written before it was recorded.
+    #[serde(default)]
+    pub hash: Option<String>,
+    /// Seconds since the Unix epoch.
+    pub installed_at: u64,
+}
+
+impl Receipt {
+    pub fn new(registry: &str, skill: &SkillSummary, files: &SkillFiles) -> Self {
+        Self {
+            registry: registry.to_string(),
+            source: skill.source.clone(),
+            skill_id: skill.skill_id.clone(),
+            git_ref: files.git_ref.clone(),
+            commit: files.commit.clone(),
+            hash: Some(content_hash(&files.files)),
+            installed_at: now(),
+        }
+    }
+
+    pub fn key(&self) -> SkillKey {
+        SkillKey::new(&self.source, &self.skill_id)
+    }
+
+    /// This receipt for a reinstall of the same skill from `files`.
+    pub fn renewed(&self, files: &SkillFiles) -> Self {
+        Self {
+            git_ref: files.git_ref.clone(),
+            commit: files.commit.clone(),
+            hash: Some(content_hash(&files.files)),
+            installed_at: now(),
+            ..self.clone()
+        }
+    }
+
+    /// The receipt in the skill folder `dir`, if it has a readable one.
+    pub fn read(dir: &Path) -> Option<Self> {
+        let json = std::fs::read_to_string(dir.join(RECEIPT_FILE)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+}
+
+/// A skill folder found in an install target.
+#[derive(Debug, Clone)]
+pub struct InstalledSkill {
+    pub target: InstallTarget,
+    pub dir: PathBuf,
+    pub manifest: SkillManifest,
+    /// Missing for skills installed by other tools or by hand.
+    pub receipt: Option<Receipt>,
+}
+
+impl InstalledSkill {
+    pub fn folder(&self) -> String {
+        self.dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Whether `query` names this skill: its folder, its skill id, or
+    /// `source/skill_id`.
+    pub fn matches(&self, query: &str) -> bool {
+        let key = self.key();
+        [self.folder(), key.skill_id.clone(), key.to_string()]
+            .iter()
+            .any(|name| name == query.trim())
+    }
+
+    /// Whether this copy is the skill `key` names: installed from its
+    /// source, or, without a receipt, the folder `key` lists it under.
+    pub fn is_copy_of(&self, key: &SkillKey) -> bool {
+        self.key() == *key
+    }
+
+    /// Whether this copy may be `key`'s skill. A copy without a receipt has
+    /// no origin to compare, so its folder or frontmatter name is compared
+    /// with the skill id instead. Good enough to mark a listing as
+    /// installed, but not to pick folders to remove.
+    pub fn may_be_copy_of(&self, key: &SkillKey) -> bool {
+        self.is_copy_of(key)
+            || (self.receipt.is_none()
+                && (self.folder() == key.skill_id
+                    || self.manifest.name.as_deref() == Some(key.skill_id.as_str())))
+    }
+
+    /// Whether the files changed since skillsm installed them. Copies whose
+    /// receipt predates the recorded hash count as unedited; a folder that
+    /// cannot be read counts as edited.
+    pub fn edited(&self) -> bool {
+        self.receipt
+            .as_ref()
+            .is_some_and(|receipt| edited_since(&self.dir, receipt))
+    }
+
+    /// The skill's key in the registry it came from, or, without a receipt,
+    /// its folder under the target directory.
+    pub fn key(&self) -> SkillKey {
+        match &self.receipt {
+            Some(receipt) => receipt.key(),
+            None => SkillKey::new(self.target.dir.display().to_string(), self.folder()),
+        }
+    }
+}
+
+/// Skill folders directly inside `target`'s directory, sorted by folder
+/// name. A directory that does not exist yet holds none. Symlinked folders,
+/// as `npx skills` creates, are followed.
+pub fn installed_in(target: &InstallTarget) -> Result<Vec<InstalledSkill>> {
+    let entries = match std::fs::read_dir(&target.dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut skills = Vec::new();
+    for entry in entries {
+        let dir = entry?.path();
+        let hidden = dir
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if hidden {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(dir.join("SKILL.md")) else {
+            continue;
+        };
+        let (frontmatter, _) = parse::split_frontmatter(&content);
+        skills.push(InstalledSkill {
+            target: target.clone(),
+            manifest: frontmatter.map(parse::parse_manifest).unwrap_or_default(),
+            receipt: Receipt::read(&dir),
+            dir,
+        });
+    }
+    skills.sort_by_key(|skill| skill.folder());
+    Ok(skills)
+}
+
+/// An installed folder about to be moved to the trash, and what goes with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Removal {
+    pub dir: PathBuf,
+    /// Where `dir` points when it is a symlink; only the link is removed.
+    pub link: Option<PathBuf>,
+    /// Files inside `dir`, relative to it; empty for a symlink.
+    pub files: Vec<String>,
+}
+
+impl Removal {
+    pub fn new(dir: &Path) -> Result<Self> {
+        if dir.symlink_metadata()?.is_symlink() {
+            return Ok(Self {
+                dir: dir.to_path_buf(),
+                link: Some(std::fs::read_link(dir)?),
+                files: Vec::new(),
+            });
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            link: None,
+            files: files_under(dir)?,
+        })
+    }
+}
+
+/// Every file under `dir` as a sorted, `/`-separated relative path.
+/// Symlinks inside are listed, not followed.
+fn files_under(dir: &Path) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(dir) {
+                let relative = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push(relative);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Digest of a skill folder's file paths and contents, for telling whether
+/// two copies differ. The receipt and file modes are left out.
+pub fn content_hash(files: &[SkillFile]) -> String {
+    let mut files: Vec<&SkillFile> = files.iter().filter(|f| f.path != RECEIPT_FILE).collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut hasher = Sha1::new();
+    for file in files {
+        hasher.update(file.path.as_bytes());
+        hasher.update([0]);
+        hasher.update((file.contents.len() as u64).to_le_bytes());
+        hasher.update(&file.contents);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// [`content_hash`] of the installed folder `dir`.
+pub fn folder_hash(dir: &Path) -> Result<String> {
+    let mut files = Vec::new();
+    for path in files_under(dir)? {
+        files.push(SkillFile {
+            contents: std::fs::read(dir.join(&path))?,
+            path,
+            executable: false,
+        });
+    }
+    Ok(content_hash(&files))
+}
+
+impl fmt::Display for Removal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.link {
+            Some(link) => write!(f, "{} (link to {})", self.dir.display(), link.display()),
+            None => {
+                write!(f, "{}", self.dir.display())?;
+                for file in &self.files {
+                    write!(f, "\n  {}", file)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Move the installed folder `dir` into `trash`, returning where it went.
+/// Moving it back undoes the uninstall.
+pub fn uninstall(dir: &Path, trash: &Path) -> Result<PathBuf> {
+    let folder = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| AppError::NotFound(dir.display().to_string()))?;
+    let secs = now();
+    std::fs::create_dir_all(trash)?;
+
+    let mut dest = trash.join(format!("{}-{}", folder, secs));
+    let mut n = 1;
+    while dest.symlink_metadata().is_ok() {
+        n += 1;
+        dest = trash.join(format!("{}-{}-{}", folder, secs, n));
+    }
+
+    if std::fs::rename(dir, &dest).is_err() {
+        // The trash is on another filesystem: copy, then remove.
+        if let Err(e) = copy_path(dir, &dest) {
+            let _ = remove_path(&dest);
+            return Err(e.into());
+        }
+        remove_path(dir)?;
+    }
+    Ok(dest)
+}
+
+/// Seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Copy a file, symlink or directory tree.
+fn copy_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    let meta = from.symlink_metadata()?;
+    if meta.is_symlink() {
+        copy_link(from, to)
+    } else if meta.is_dir() {
+        std::fs::create_dir_all(to)?;
+        for entry in std::fs::read_dir(from)? {
+            let entry = entry?;
+            copy_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        std::fs::copy(from, to).map(|_| ())
+    }
+}
+
+#[cfg(unix)]
+fn copy_link(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_link(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::copy(from, to).map(|_| ())
+}
+
+/// `path` with a leading `~` replaced by the home directory. Without one,
+/// `~` is an error rather than a folder in the current directory.
+pub fn expand_home(path: &Path) -> Result<PathBuf> {
+    let Ok(rest) = path.strip_prefix("~") else {
+        return Ok(path.to_path_buf());
+    };
+    match std::env::var_os("HOME").filter(|home| !home.is_empty()) {
+        Some(home) => Ok(PathBuf::from(home).join(rest)),
+        None => Err(AppError::Config(format!(
+            "{} needs the HOME environment variable to be set",
+            path.display()
+        ))),
+    }
+}
+
+/// Name of the folder the skill `skill_id` installs into: the last segment
+/// of a folder path, as repository catalogs use for folders that share a
+/// name, or the id itself.
+pub fn install_folder(skill_id: &str) -> Result<String> {
+    folder_name(skill_id.rsplit('/').next().unwrap_or(skill_id))
+}
+
+/// Name of the folder `skill_id` installs into, refusing ids that are not a
+/// single plain path segment.
+pub fn folder_name(skill_id: &str) -> Result<String> {
+    let name = skill_id.trim();
+    let plain = !name.is_empty()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\'])
+        && Path::new(name)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+    if plain {
+        Ok(name.to_string())
+    } else {
+        Err(AppError::InvalidSkill(format!(
+            "{:?} is not a valid folder name",
+            skill_id
+        )))
+    }
+}
+
+/// Write `files` and `receipt` to `skills_dir/folder`. A previous install
+/// of the same skill moves into `trash`, as with [`update`]; returns where
+/// it went.
+pub fn install(
+    skills_dir: &Path,
+    folder: &str,
+    files: &SkillFiles,
+    receipt: &Receipt,
+    trash: &Path,
+) -> Result<Option<PathBuf>> {
+    let folder = folder_name(folder)?;
+    let dest = skills_dir.join(&folder);
+    if dest.symlink_metadata().is_ok() {
+        replaceable(&dest, receipt)?;
+        return update(&dest, files, receipt, trash).map(Some);
+    }
+
+    let staging = stage(skills_dir, &folder, files, receipt)?;
+    if let Err(e) = std::fs::rename(&staging, &dest) {
+        let _ = remove_path(&staging);
+        return Err(e.into());
+    }
+    Ok(None)
+}
+
+/// Refuse to replace the installed folder `dir` with the skill `receipt`
+/// describes when it holds another skill or has local changes.
+fn replaceable(dir: &Path, receipt: &Receipt) -> Result<()> {
+    let Some(installed) = Receipt::read(dir) else {
+        return Ok(());
+    };
+    if installed.key() != receipt.key() {
+        return Err(AppError::InvalidSkill(format!(
+            "{} already holds {}; uninstall it first",
+            dir.display(),
+            installed.key()
+        )));
+    }
+    if edited_since(dir, &installed) {
+        return Err(AppError::InvalidSkill(format!(
+            "{} has local changes; uninstall it first to keep them in the trash",
+            dir.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Whether the files in `dir` changed since `receipt` was written. Receipts
+/// that predate the recorded hash cannot tell, and count as unedited; a
+/// folder that cannot be read counts as edited.
+fn edited_since(dir: &Path, receipt: &Receipt) -> bool {
+    match &receipt.hash {
+        Some(hash) => folder_hash(dir).map_or(true, |h| h != *hash),
+        None => false,
+    }
+}
+
+/// Replace the installed folder `dir` with `files` and `receipt`, moving the
+/// old copy into `trash` as [`uninstall`] does. Returns where it went.
+pub fn update(dir: &Path, files: &SkillFiles, receipt: &Receipt, trash: &Path) -> Result<PathBuf> {
+    let (Some(skills_dir), Some(folder)) = (dir.parent(), dir.file_name()) else {
+        return Err(AppError::NotFound(dir.display().to_string()));
+    };
+    let staging = stage(skills_dir, &folder.to_string_lossy(), files, receipt)?;
+
+    let old = match uninstall(dir, trash) {
+        Ok(old) => old,
+        Err(e) => {
+            let _ = remove_path(&staging);
+            return Err(e);
+        }
+    };
+    if let Err(e) = std::fs::rename(&staging, dir) {
+        let _ = std::fs::rename(&old, dir);
+        let _ = remove_path(&staging);
+        return Err(e.into());
+    }
+    Ok(old)
+}
+
+/// Write `files` and `receipt` to a staging folder beside
+/// `skills_dir/folder`, ready to be renamed into place.
+fn stage(
+    skills_dir: &Path,
+    folder: &str,
+    files: &SkillFiles,
+    receipt: &Receipt,
+) -> Result<PathBuf> {
+    std::fs::create_dir_all(skills_dir)?;
+    let staging = skills_dir.join(format!(".{}.installing-{}", folder, std::process::id()));
+    remove_path(&staging)?;
+    if let Err(e) = write_files(&staging, files, receipt) {
+        let _ = remove_path(&staging);
+        return Err(e);
+    }
+    Ok(staging)
+}
+
+fn write_files(dir: &Path, files: &SkillFiles, receipt: &Receipt) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for file in &files.files {
+        let relative = Path::new(&file.path);
+        let safe = relative.components().count() > 0
+            && relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)));
+        if !safe {
+            return Err(AppError::InvalidSkill(format!(
+                "file path {:?} leaves the skill folder",
+                file.path
+            )));
+        }
+        let path = dir.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, &file.contents)?;
+        if file.executable {
+            mark_executable(&path)?;
+        }
+    }
+    let json = serde_json::to_string_pretty(receipt)
+        .map_err(|e| AppError::Parse(format!("install receipt: {}", e)))?;
+    std::fs::write(dir.join(RECEIPT_FILE), json)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn mark_executable(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn mark_executable(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Remove a file, symlink or directory tree, if there is one.
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    match path.symlink_metadata() {
+        Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(path),
+        Ok(_) => std::fs::remove_file(path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
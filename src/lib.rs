@@ -1,4 +1,6 @@
 pub mod app;
+pub mod config;
 pub mod data;
 pub mod error;
+pub mod install;
 pub mod ui;
@@ -1,28 +1,84 @@
-mod app;
-mod data;
-mod error;
-mod ui;
-
-use app::{
-    events::{Action, Event},
-    App,
-};
+use clap::{Parser, Subcommand};
 use crossterm::{
     event::{self, Event as CrosstermEvent, KeyEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use data::{SkillsClient, ViewKind};
 use ratatui::prelude::*;
-use std::{io::stdout, time::Duration};
+use skillsm::app::{
+    self,
+    events::{Action, Event, Request},
+    state::TabId,
+    App,
+};
+use skillsm::config::{self, Config};
+use skillsm::data::installed::Update;
+use skillsm::data::{
+    registry, DocumentCache, InstalledRegistry, RegistryIndex, RepoRegistry, ResponseDump,
+    SkillKey, SkillRegistry, SkillSummary, SkillsClientBuilder, ViewCache,
+};
+use skillsm::error::AppError;
+use skillsm::install::{self as installer, Installer, Receipt, Removal, TargetMemory};
+use skillsm::ui;
+use std::{
+    io::stdout,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 use tokio::sync::mpsc;
 
+/// Browse and install agent skills from skills.sh.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    /// Save every raw HTML/JSON response to DIR, for attaching to bug reports.
+    #[arg(long, value_name = "DIR")]
+    dump_responses: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Manage static index.json registries.
+    #[command(subcommand)]
+    Index(IndexCommand),
+    /// Move an installed skill to the trash, from every install target.
+    Uninstall {
+        /// Folder name, skill id or source/skill_id of the skill.
+        skill: String,
+        /// Do not ask for confirmation.
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum IndexCommand {
+    /// Generate an index.json from a directory of skills.
+    Build {
+        /// Directory to scan for SKILL.md files.
+        dir: PathBuf,
+        /// Where to write the index [default: DIR/index.json].
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    if let Some(command) = cli.command {
+        return run_command(command);
+    }
+
     enable_raw_mode()?;
     execute!(stdout(), EnterAlternateScreen)?;
 
-    let result = run().await;
+    let result = run(cli).await;
 
     disable_raw_mode()?;
     execute!(stdout(), LeaveAlternateScreen)?;
@@ -30,85 +86,246 @@ async fn main() -> anyhow::Result<()> {
     result
 }
 
-async fn run() -> anyhow::Result<()> {
+fn run_command(command: Command) -> anyhow::Result<()> {
+    match command {
+        Command::Index(IndexCommand::Build { dir, output }) => {
+            let output = output.unwrap_or_else(|| dir.join("index.json"));
+            let base = match output.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                _ => PathBuf::from("."),
+            };
+            let index = RegistryIndex::build(&dir, &base)?;
+            std::fs::write(&output, index.to_json()?)?;
+            println!(
+                "Wrote {} skills to {}",
+                index.skills.len(),
+                output.display()
+            );
+            Ok(())
+        }
+        Command::Uninstall { skill, yes } => {
+            let config = Config::load()?;
+            let project = std::env::current_dir()
+                .ok()
+                .map(|dir| installer::project_root(&dir));
+            let (targets, skipped) =
+                installer::targets(&installer::agents(&config.install), project.as_deref());
+            for warning in &skipped {
+                eprintln!("Warning: {}", warning);
+            }
+            let mut removals = Vec::new();
+            for target in &targets {
+                for copy in installer::installed_in(target)? {
+                    if copy.matches(&skill) {
+                        removals.push(Removal::new(&copy.dir)?);
+                    }
+                }
+            }
+            if removals.is_empty() {
+                anyhow::bail!("{} is not installed", skill);
+            }
+
+            println!("These folders move to the trash:");
+            for removal in &removals {
+                println!("{}", removal);
+            }
+            if !yes {
+                print!("Uninstall? [y/N] ");
+                std::io::Write::flush(&mut stdout())?;
+                let mut answer = String::new();
+                std::io::stdin().read_line(&mut answer)?;
+                if !matches!(answer.trim(), "y" | "Y" | "yes") {
+                    println!("Cancelled");
+                    return Ok(());
+                }
+            }
+
+            let trash = config::data_dir()
+                .map(|dir| dir.join("trash"))
+                .ok_or_else(|| anyhow::anyhow!("no data directory to keep the trash in"))?;
+            for removal in &removals {
+                let dest = installer::uninstall(&removal.dir, &trash)?;
+                println!("Moved {} to {}", removal.dir.display(), dest.display());
+            }
+            println!("Move a folder back to restore it.");
+            Ok(())
+        }
+    }
+}
+
+async fn run(cli: Cli) -> anyhow::Result<()> {
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
-    let mut app = App::new();
+
+    let config = Config::load()?;
 
     let (tx, mut rx) = mpsc::channel::<Event>(32);
-    let client = SkillsClient::new()?;
+    let mut builder = SkillsClientBuilder::from_config(&config)?;
+    if let Some(dir) = config::cache_dir() {
+        let documents = DocumentCache::open(dir.join("docs"), config.cache.max_document_bytes());
+        builder = builder
+            .document_cache(documents)
+            .git_checkouts(dir.join("repos"));
+    }
+    if let Some(dir) = &cli.dump_responses {
+        builder = builder.response_dump(ResponseDump::new(dir)?);
+    }
+    let project = std::env::current_dir()
+        .ok()
+        .map(|dir| installer::project_root(&dir));
+    let mut registries = registry::from_config(&config, &builder)?;
+    // Skills in every target are listed, whichever installer is configured.
+    let (targets, skipped) =
+        installer::targets(&installer::agents(&config.install), project.as_deref());
+    // Shared by the repositories opened from the TUI.
+    let github = builder.build()?;
+    let installed =
+        Arc::new(InstalledRegistry::new(targets).upstream(registries.clone(), github.clone()));
+    registries.push(installed.clone());
+    // Each registry caches its leaderboards in its own directory.
+    let view_caches: Vec<Option<ViewCache>> = registries
+        .iter()
+        .map(|r| {
+            config::cache_dir()
+                .filter(|_| r.caches_views())
+                .map(|dir| ViewCache::new(dir.join("views").join(r.id())))
+        })
+        .collect();
 
-    {
-        let view = app.state.current_view;
-        if let Some(view_state) = app.state.views.get_mut(&view) {
-            view_state.loading = true;
+    let mut app = App::new(registries);
+    app.state.cache_ttl = config.cache.ttl();
+    app.state.installer = Installer::from_config(&config.install, project.as_deref());
+    if !skipped.is_empty() {
+        app.update(Event::Warning(skipped.join("; ")));
+    }
+    let target_memory = config::data_dir().map(|dir| TargetMemory::new(dir.join("targets.json")));
+    if let (Some(memory), Some(project)) = (&target_memory, &project) {
+        if let Some(id) = memory.get(project) {
+            if let Some(index) = app
+                .state
+                .install_targets()
+                .iter()
+                .position(|t| t.id() == id)
+            {
+                app.state.preferred_target = index;
+            }
         }
     }
-    spawn_fetch_task(tx.clone(), client.clone(), app.state.current_view);
+
+    // Show cached leaderboards straight away; stale ones refresh below.
+    for tab in app.state.tabs.clone() {
+        if let Some(cached) = view_caches[tab.registry]
+            .as_ref()
+            .and_then(|cache| cache.load(tab.view))
+        {
+            app.update(Event::ViewLoaded {
+                tab,
+                fetched_at: cached.fetched_at(),
+                skills: cached.skills,
+                from_cache: true,
+            });
+        }
+    }
+    if let Some(Action::FetchView(tab)) = app.refresh_if_stale() {
+        let registry = app.state.registries[tab.registry].clone();
+        let cache = view_caches[tab.registry].clone();
+        spawn_fetch_task(tx.clone(), registry, cache, tab);
+    }
+    // The installed skills are marked in every view, so scan them now.
+    if let Some(tab) = app.state.installed_tab() {
+        let registry = app.state.registries[tab.registry].clone();
+        spawn_fetch_task(tx.clone(), registry, None, tab);
+    }
 
     loop {
+        app.state.rate_limit = app.state.current_registry().rate_limit();
         terminal.draw(|frame| ui::draw(frame, &app))?;
 
         // Process any pending events first
+        let mut actions = Vec::new();
         while let Ok(event) = rx.try_recv() {
-            let actions = app.update(event);
-            for action in actions {
-                match action {
-                    Action::FetchView(view) => {
-                        spawn_fetch_task(tx.clone(), client.clone(), view);
-                    }
-                    Action::FetchDetail { source, skill_id } => {
-                        spawn_fetch_readme_task(tx.clone(), client.clone(), source, skill_id);
-                    }
-                    Action::InstallInteractive(skill) => {
-                        // Temporarily exit TUI to run interactive command
-                        execute!(stdout(), LeaveAlternateScreen)?;
-                        disable_raw_mode()?;
-
-                        let output = run_install_interactive(&skill);
-
-                        enable_raw_mode()?;
-                        execute!(stdout(), EnterAlternateScreen)?;
-                        terminal.clear()?;
-
-                        // Show result in modal
-                        app.state.mode = app::state::Mode::Installing;
-                        app.state.install_command = skill.install_command();
-                        app.state.install_output = output;
-                    }
-                }
-            }
+            actions.extend(app.update(event));
         }
 
         if event::poll(Duration::from_millis(50))? {
             if let CrosstermEvent::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
-                    let actions = app.update(Event::Key(key));
-                    for action in actions {
-                        match action {
-                            Action::FetchView(view) => {
-                                spawn_fetch_task(tx.clone(), client.clone(), view);
-                            }
-                            Action::FetchDetail { source, skill_id } => {
-                                spawn_fetch_readme_task(tx.clone(), client.clone(), source, skill_id);
-                            }
-                            Action::InstallInteractive(skill) => {
-                                // Temporarily exit TUI to run interactive command
-                                execute!(stdout(), LeaveAlternateScreen)?;
-                                disable_raw_mode()?;
-
-                                let output = run_install_interactive(&skill);
-
-                                enable_raw_mode()?;
-                                execute!(stdout(), EnterAlternateScreen)?;
-                                terminal.clear()?;
-
-                                // Show result in modal
-                                app.state.mode = app::state::Mode::Installing;
-                                app.state.install_command = skill.install_command();
-                                app.state.install_output = output;
-                            }
+                    actions.extend(app.update(Event::Key(key)));
+                }
+            }
+        }
+
+        for action in actions {
+            match action {
+                Action::FetchView(tab) => {
+                    let registry = app.state.registries[tab.registry].clone();
+                    let cache = view_caches.get(tab.registry).cloned().flatten();
+                    spawn_fetch_task(tx.clone(), registry, cache, tab);
+                }
+                Action::BrowseRepo(repo) => {
+                    let registry = Arc::new(RepoRegistry::new(repo, github.clone()));
+                    if let Some(Action::FetchView(tab)) = app.open_registry(registry) {
+                        let registry = app.state.registries[tab.registry].clone();
+                        spawn_fetch_task(tx.clone(), registry, None, tab);
+                    }
+                }
+                Action::PlanUninstall { name, key } => {
+                    spawn_uninstall_plan_task(tx.clone(), installed.clone(), name, key);
+                }
+                Action::Uninstall(removals) => {
+                    spawn_uninstall_task(tx.clone(), removals);
+                }
+                Action::UpdateAll => {
+                    spawn_update_task(tx.clone(), installed.updates());
+                }
+                Action::FetchDetail {
+                    registry,
+                    key,
+                    git_ref,
+                } => {
+                    let source = app.state.registries[registry].clone();
+                    spawn_fetch_readme_task(tx.clone(), source, registry, key, git_ref);
+                }
+                Action::Install {
+                    registry,
+                    skill,
+                    target,
+                } => {
+                    let source = app.state.registries[registry].clone();
+                    if let Some(target) = target {
+                        if let (Some(memory), Some(project)) = (&target_memory, &project) {
+                            // Forgetting the choice only costs a keypress next time.
+                            let _ = memory.remember(project, &target.id());
                         }
+                        spawn_install_task(tx.clone(), source, target.dir, skill);
+                        continue;
                     }
+
+                    let Some(command) = source.install_command(&skill) else {
+                        app.state.status_message =
+                            Some("This registry has no install command".to_string());
+                        continue;
+                    };
+
+                    // Temporarily exit TUI to run interactive command
+                    execute!(stdout(), LeaveAlternateScreen)?;
+                    disable_raw_mode()?;
+
+                    let output = match run_install_interactive(&command) {
+                        Ok(()) => "Installation completed successfully".to_string(),
+                        Err(e) => match e.hint() {
+                            Some(hint) => format!("{}\n\n{}", e, hint),
+                            None => e.to_string(),
+                        },
+                    };
+
+                    enable_raw_mode()?;
+                    execute!(stdout(), EnterAlternateScreen)?;
+                    terminal.clear()?;
+
+                    // Show result in modal
+                    app.state.mode = app::state::Mode::Installing;
+                    app.state.install_command = command.join(" ");
+                    app.state.install_output = output;
                 }
             }
         }
@@ -121,46 +338,243 @@ async fn run() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn run_install_interactive(skill: &data::SkillSummary) -> String {
+fn run_install_interactive(command: &[String]) -> Result<(), AppError> {
     use std::process::Command;
 
-    let mut cmd = Command::new("npx");
-    cmd.args([
-        "skills",
-        "add",
-        &format!("https://github.com/{}", skill.source),
-        "--skill",
-        &skill.skill_id,
-    ]);
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| AppError::InstallFailed("empty install command".to_string()))?;
+    let mut cmd = Command::new(program);
+    cmd.args(args);
 
     // Run interactively with user's terminal
     cmd.stdin(std::process::Stdio::inherit())
         .stdout(std::process::Stdio::inherit())
         .stderr(std::process::Stdio::inherit());
 
-    let status = cmd.status();
+    let status = cmd
+        .status()
+        .map_err(|e| AppError::InstallFailed(format!("could not run {}: {}", program, e)))?;
 
-    match status {
-        Ok(exit) if exit.success() => {
-            "Installation completed successfully".to_string()
-        }
-        Ok(exit) => {
-            format!("Installation failed with exit code: {}", exit)
-        }
-        Err(e) => {
-            format!("Failed to run install command: {}", e)
-        }
+    if status.success() {
+        Ok(())
+    } else {
+        Err(AppError::InstallFailed(format!(
+            "{} exited with {}",
+            program, status
+        )))
     }
 }
 
-fn spawn_fetch_task(tx: mpsc::Sender<Event>, client: SkillsClient, view: ViewKind) {
+fn spawn_install_task(
+    tx: mpsc::Sender<Event>,
+    registry: Arc<dyn SkillRegistry>,
+    skills_dir: PathBuf,
+    skill: SkillSummary,
+) {
     tokio::spawn(async move {
-        match client.fetch_skills(view).await {
-            Ok(skills) => {
-                let _ = tx.send(Event::ViewLoaded { view, skills }).await;
+        let result = async {
+            let folder = installer::install_folder(&skill.skill_id)?;
+            let files = registry.skill_files(&skill.key(), None).await?;
+            let written = files.clone();
+            let receipt = Receipt::new(registry.id(), &skill, &files);
+            let trash = config::data_dir()
+                .map(|dir| dir.join("trash"))
+                .ok_or_else(|| {
+                    AppError::InstallFailed("no data directory to keep replaced copies in".into())
+                })?;
+            let dest = skills_dir.join(&folder);
+            let replaced = tokio::task::spawn_blocking(move || {
+                installer::install(&skills_dir, &folder, &written, &receipt, &trash).map_err(|e| {
+                    match e {
+                        AppError::Io(e) => AppError::InstallFailed(format!(
+                            "could not write to {}: {}",
+                            skills_dir.display(),
+                            e
+                        )),
+                        e => e,
+                    }
+                })
+            })
+            .await
+            .map_err(|e| AppError::Io(std::io::Error::other(e)))??;
+            Ok::<_, AppError>((dest, replaced, files))
+        }
+        .await;
+
+        let output = match result {
+            Ok((dest, replaced, files)) => {
+                let mut output = format!("Installed {} into {}", skill.name, dest.display());
+                if let Some(git_ref) = &files.git_ref {
+                    output.push_str(&format!(" (at {})", git_ref));
+                }
+                output.push('\n');
+                for file in &files.files {
+                    output.push_str(&format!("\n  {}", file.path));
+                }
+                if let Some(replaced) = replaced {
+                    output.push_str(&format!(
+                        "\n\nThe previous copy is in {}",
+                        replaced.display()
+                    ));
+                }
+                output
+            }
+            Err(e) => match e.hint() {
+                Some(hint) => format!("{}\n\n{}", e, hint),
+                None => e.to_string(),
+            },
+        };
+        let _ = tx.send(Event::InstallFinished(output)).await;
+    });
+}
+
+fn spawn_uninstall_plan_task(
+    tx: mpsc::Sender<Event>,
+    installed: Arc<InstalledRegistry>,
+    name: String,
+    key: SkillKey,
+) {
+    tokio::spawn(async move {
+        let dirs: Vec<PathBuf> = installed
+            .copies(&key)
+            .into_iter()
+            .map(|copy| copy.dir)
+            .collect();
+        let removals = tokio::task::spawn_blocking(move || {
+            dirs.iter()
+                .map(|dir| Removal::new(dir))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .await
+        .map_err(|e| AppError::Io(std::io::Error::other(e)))
+        .and_then(|result| result);
+        let event = match removals {
+            Ok(removals) => Event::UninstallPlanned { name, removals },
+            Err(e) => Event::error(Request::Task, &e),
+        };
+        let _ = tx.send(event).await;
+    });
+}
+
+fn spawn_uninstall_task(tx: mpsc::Sender<Event>, removals: Vec<Removal>) {
+    tokio::spawn(async move {
+        let output = tokio::task::spawn_blocking(move || {
+            let Some(trash) = config::data_dir().map(|dir| dir.join("trash")) else {
+                return "Cannot uninstall: no data directory to keep the trash in".to_string();
+            };
+            let mut output = String::new();
+            for removal in &removals {
+                match installer::uninstall(&removal.dir, &trash) {
+                    Ok(dest) => output.push_str(&format!(
+                        "Moved {} to {}\n",
+                        removal.dir.display(),
+                        dest.display()
+                    )),
+                    Err(e) => output.push_str(&format!(
+                        "Could not remove {}: {}\n",
+                        removal.dir.display(),
+                        e
+                    )),
+                }
+            }
+            output.push_str("\nMove a folder back to restore it.");
+            output
+        })
+        .await
+        .unwrap_or_else(|e| e.to_string());
+        let _ = tx.send(Event::InstallFinished(output)).await;
+    });
+}
+
+fn spawn_update_task(tx: mpsc::Sender<Event>, updates: Vec<Update>) {
+    tokio::spawn(async move {
+        let output = tokio::task::spawn_blocking(move || {
+            let Some(trash) = config::data_dir().map(|dir| dir.join("trash")) else {
+                return "Cannot update: no data directory to keep the old copies in".to_string();
+            };
+            let mut output = String::new();
+            let mut replaced = false;
+            for update in &updates {
+                for copy in &update.copies {
+                    if copy.edited() {
+                        output.push_str(&format!(
+                            "Skipped {} in {}: it has local changes\n",
+                            update.name,
+                            copy.dir.display()
+                        ));
+                        continue;
+                    }
+                    let result = match &copy.receipt {
+                        Some(receipt) => installer::update(
+                            &copy.dir,
+                            &update.upstream,
+                            &receipt.renewed(&update.upstream),
+                            &trash,
+                        ),
+                        None => Err(AppError::NotFound(copy.dir.display().to_string())),
+                    };
+                    match result {
+                        Ok(_) => {
+                            replaced = true;
+                            output.push_str(&format!(
+                                "Updated {} in {} ({})\n",
+                                update.name,
+                                copy.dir.display(),
+                                update.range()
+                            ));
+                        }
+                        Err(e) => output.push_str(&format!(
+                            "Could not update {} in {}: {}\n",
+                            update.name,
+                            copy.dir.display(),
+                            e
+                        )),
+                    }
+                }
+            }
+            if replaced {
+                output.push_str(&format!("\nThe old copies are in {}.", trash.display()));
+            }
+            output
+        })
+        .await
+        .unwrap_or_else(|e| e.to_string());
+        let _ = tx.send(Event::InstallFinished(output)).await;
+    });
+}
+
+fn spawn_fetch_task(
+    tx: mpsc::Sender<Event>,
+    registry: Arc<dyn SkillRegistry>,
+    cache: Option<ViewCache>,
+    tab: TabId,
+) {
+    tokio::spawn(async move {
+        match registry.list(tab.view).await {
+            Ok(leaderboard) => {
+                let skills = leaderboard.skills;
+                let fetched_at = SystemTime::now();
+                if let Some(cache) = &cache {
+                    // A failed cache write only costs the next startup a fetch.
+                    let _ = cache.store(tab.view, &skills, fetched_at);
+                }
+                let _ = tx
+                    .send(Event::ViewLoaded {
+                        tab,
+                        skills,
+                        fetched_at,
+                        from_cache: false,
+                    })
+                    .await;
+                if !leaderboard.warnings.is_empty() {
+                    let message =
+                        format!("{}: {}", registry.name(), leaderboard.warnings.join("; "));
+                    let _ = tx.send(Event::Warning(message)).await;
+                }
             }
             Err(e) => {
-                let _ = tx.send(Event::Error(e.to_string())).await;
+                let _ = tx.send(Event::error(Request::View(tab), &e)).await;
             }
         }
     });
@@ -168,22 +582,41 @@ fn spawn_fetch_task(tx: mpsc::Sender<Event>, client: SkillsClient, view: ViewKin
 
 fn spawn_fetch_readme_task(
     tx: mpsc::Sender<Event>,
-    client: SkillsClient,
-    source: String,
-    skill_id: String,
+    registry: Arc<dyn SkillRegistry>,
+    index: usize,
+    key: SkillKey,
+    git_ref: Option<String>,
 ) {
     tokio::spawn(async move {
-        match client.fetch_readme(&source, &skill_id).await {
-            Ok(markdown) => {
+        // Show the cached copy straight away, then replace it once revalidated.
+        let cached = git_ref
+            .is_none()
+            .then(|| registry.cached_detail(&key))
+            .flatten();
+        let shown_cached = cached.is_some();
+        if let Some(detail) = cached {
+            let _ = tx
+                .send(Event::DetailLoaded {
+                    registry: index,
+                    key: key.clone(),
+                    detail: Box::new(detail),
+                })
+                .await;
+        }
+
+        match registry.fetch_detail(&key, git_ref.as_deref()).await {
+            Ok(detail) => {
                 let _ = tx
                     .send(Event::DetailLoaded {
-                        key: skill_id,
-                        markdown,
+                        registry: index,
+                        key,
+                        detail: Box::new(detail),
                     })
                     .await;
             }
+            Err(e) if shown_cached && e.is_transient() => {}
             Err(e) => {
-                let _ = tx.send(Event::Error(e.to_string())).await;
+                let _ = tx.send(Event::error(Request::Detail, &e)).await;
             }
         }
     });
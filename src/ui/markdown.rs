@@ -0,0 +1,62 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+/// Render markdown into styled text for the detail screen.
+///
+/// `tui-markdown` builds on `ratatui-core`, whose types are distinct from the
+/// ones re-exported by the `ratatui` version we use, so the output is
+/// converted span by span.
+pub fn render(markdown: &str) -> Text<'_> {
+    let rendered = tui_markdown::from_str(markdown);
+
+    let lines: Vec<Line> = rendered
+        .lines
+        .into_iter()
+        .map(|line| {
+            let spans: Vec<Span> = line
+                .spans
+                .into_iter()
+                .map(|span| Span::styled(span.content, convert_style(span.style)))
+                .collect();
+            Line::from(spans).style(convert_style(line.style))
+        })
+        .collect();
+
+    Text::from(lines).style(convert_style(rendered.style))
+}
+
+fn convert_style(style: ratatui_core::style::Style) -> Style {
+    let mut converted = Style::default()
+        .add_modifier(Modifier::from_bits_truncate(style.add_modifier.bits()))
+        .remove_modifier(Modifier::from_bits_truncate(style.sub_modifier.bits()));
+    converted.fg = style.fg.map(convert_color);
+    converted.bg = style.bg.map(convert_color);
+    converted
+}
+
+fn convert_color(color: ratatui_core::style::Color) -> Color {
+    use ratatui_core::style::Color as Core;
+    match color {
+        Core::Reset => Color::Reset,
+        Core::Black => Color::Black,
+        Core::Red => Color::Red,
+        Core::Green => Color::Green,
+        Core::Yellow => Color::Yellow,
+        Core::Blue => Color::Blue,
+        Core::Magenta => Color::Magenta,
+        Core::Cyan => Color::Cyan,
+        Core::Gray => Color::Gray,
+        Core::DarkGray => Color::DarkGray,
+        Core::LightRed => Color::LightRed,
+        Core::LightGreen => Color::LightGreen,
+        Core::LightYellow => Color::LightYellow,
+        Core::LightBlue => Color::LightBlue,
+        Core::LightMagenta => Color::LightMagenta,
+        Core::LightCyan => Color::LightCyan,
+        Core::White => Color::White,
+        Core::Rgb(r, g, b) => Color::Rgb(r, g, b),
+        Core::Indexed(i) => Color::Indexed(i),
+    }
+}
@@ -1,3 +1,4 @@
+pub mod markdown;
 pub mod widgets;
 
 use crate::app::state::Mode;
@@ -7,23 +8,44 @@ use ratatui::{
     widgets::TableState,
     Frame,
 };
-use widgets::{DetailWidget, HelpWidget, InstallModal, SkillListWidget, StatusBar, TabsWidget};
+use widgets::{
+    DetailWidget, HelpWidget, InstallModal, SkillListWidget, StatusBar, TabsWidget, TargetPicker,
+    UninstallModal,
+};
 
 pub fn draw(frame: &mut Frame, app: &App) {
-    if app.state.mode == Mode::Detail {
+    if matches!(app.state.mode, Mode::Detail | Mode::RefInput) {
         draw_detail_screen(frame, app);
         return;
     }
 
-    if app.state.mode == Mode::Installing {
+    if matches!(app.state.mode, Mode::Installing | Mode::Uninstalling) {
         draw_list_screen(frame, app);
-        let modal = InstallModal::new(&app.state.install_command, &app.state.install_output);
+        let mut modal = InstallModal::new(&app.state.install_command, &app.state.install_output);
+        if app.state.mode == Mode::Uninstalling {
+            modal = modal.title(" Uninstalling Skill ");
+        }
         frame.render_widget(modal, frame.area());
         return;
     }
 
     draw_list_screen(frame, app);
 
+    if let (Mode::PickTarget, Some(pending)) = (app.state.mode, &app.state.pending_install) {
+        let picker = TargetPicker::new(
+            &pending.skill.name,
+            app.state.install_targets(),
+            pending.selected,
+        );
+        frame.render_widget(picker, frame.area());
+    }
+
+    if let (Mode::ConfirmUninstall, Some(pending)) = (app.state.mode, &app.state.pending_uninstall)
+    {
+        let modal = UninstallModal::new(&pending.name, &pending.removals);
+        frame.render_widget(modal, frame.area());
+    }
+
     if app.state.mode == Mode::Help {
         frame.render_widget(HelpWidget, frame.area());
     }
@@ -41,21 +63,34 @@ fn draw_list_screen(frame: &mut Frame, app: &App) {
         ])
         .split(frame.area());
 
-    let tabs = TabsWidget::new(app.state.current_view);
+    let tabs = TabsWidget::new(&app.state);
     frame.render_widget(tabs, chunks[0]);
 
-    let skill_list = SkillListWidget::new(view_state, app.state.current_view);
+    let tab = app.state.current_tab();
+    let label = app.state.tab_label(tab);
+    let mut skill_list =
+        SkillListWidget::new(view_state, &label).notes(app.state.current_registry().as_ref());
+    if let Some(local) = app.state.local_registry() {
+        if !app.state.current_registry().is_local() {
+            skill_list = skill_list.installed(local.as_ref());
+        }
+    }
 
     let mut table_state = TableState::default();
     table_state.select(view_state.list_state.selected());
     frame.render_stateful_widget(skill_list, chunks[1], &mut table_state);
 
+    let input = match app.state.mode {
+        Mode::RepoInput => app.state.repo_input.clone(),
+        _ => app.state.search_query.clone(),
+    };
     let status = StatusBar::new(
         app.state.mode,
         app.state.status_message.clone(),
         view_state.loading,
-        app.state.search_query.clone(),
-    );
+        input,
+    )
+    .rate_limit(app.state.rate_limit);
     frame.render_widget(status, chunks[2]);
 }
 
@@ -65,27 +100,24 @@ fn draw_detail_screen(frame: &mut Frame, app: &App) {
         .constraints([Constraint::Min(10), Constraint::Length(1)])
         .split(frame.area());
 
-    let view_state = app.state.current_view_state();
-    let selected_skill = view_state.selected_skill();
-    let markdown = selected_skill.and_then(|s| {
-        app.state
-            .detail_cache
-            .get(&s.skill_id)
-            .map(|d| d.markdown.as_str())
-    });
     let detail = DetailWidget::new(
-        selected_skill,
-        markdown,
+        app.state.selected_skill(),
+        app.state.selected_detail(),
         app.state.detail_loading,
         app.state.detail_scroll,
-    );
+        app.state.detail_raw,
+    )
+    .install_command(app.state.selected_install_command())
+    .source_url(app.state.selected_source_url())
+    .fields(app.state.selected_detail_fields());
     frame.render_widget(detail, chunks[0]);
 
     let status = StatusBar::new(
         app.state.mode,
         app.state.status_message.clone(),
         app.state.detail_loading,
-        String::new(),
-    );
+        app.state.ref_input.clone(),
+    )
+    .rate_limit(app.state.rate_limit);
     frame.render_widget(status, chunks[1]);
 }
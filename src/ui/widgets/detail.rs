@@ -1,40 +1,76 @@
-use crate::data::SkillSummary;
+use crate::data::{SkillDetail, SkillManifest, SkillSummary};
+use crate::ui::markdown;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Widget},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Paragraph, Row, Table, Widget, Wrap},
 };
 
+const MANIFEST_KEY_WIDTH: u16 = 16;
+const MAX_VALUE_LINES: usize = 3;
+
 pub struct DetailWidget<'a> {
     skill: Option<&'a SkillSummary>,
-    markdown: Option<&'a str>,
+    detail: Option<&'a SkillDetail>,
     loading: bool,
     scroll: u16,
+    raw: bool,
+    install_command: Option<String>,
+    source_url: Option<String>,
+    fields: Vec<(&'static str, String)>,
 }
 
 impl<'a> DetailWidget<'a> {
     pub fn new(
         skill: Option<&'a SkillSummary>,
-        markdown: Option<&'a str>,
+        detail: Option<&'a SkillDetail>,
         loading: bool,
         scroll: u16,
+        raw: bool,
     ) -> Self {
         Self {
             skill,
-            markdown,
+            detail,
             loading,
             scroll,
+            raw,
+            install_command: None,
+            source_url: None,
+            fields: Vec::new(),
         }
     }
+
+    /// Command line shown as the way to install the skill.
+    pub fn install_command(mut self, command: Option<String>) -> Self {
+        self.install_command = command;
+        self
+    }
+
+    /// Link shown in place of the bare source.
+    pub fn source_url(mut self, url: Option<String>) -> Self {
+        self.source_url = url;
+        self
+    }
+
+    /// Extra labelled lines shown under the ref.
+    pub fn fields(mut self, fields: Vec<(&'static str, String)>) -> Self {
+        self.fields = fields;
+        self
+    }
 }
 
 impl<'a> Widget for DetailWidget<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = if self.raw {
+            " Skill Detail (raw) "
+        } else {
+            " Skill Detail "
+        };
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(" Skill Detail ")
+            .title(title)
             .border_style(Style::default().fg(Color::Cyan));
 
         let inner = block.inner(area);
@@ -47,46 +83,183 @@ impl<'a> Widget for DetailWidget<'a> {
             return;
         };
 
+        let manifest_rows = self
+            .detail
+            .and_then(|d| d.manifest.as_ref())
+            .map(|m| manifest_rows(m, inner.width.saturating_sub(MANIFEST_KEY_WIDTH + 1)))
+            .unwrap_or_default();
+        let manifest_height = if manifest_rows.is_empty() {
+            0
+        } else {
+            manifest_rows.iter().map(|(_, h)| *h).sum::<u16>() + 2
+        };
+
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(6), Constraint::Min(1)])
+            .constraints([
+                Constraint::Length(7 + self.fields.len() as u16),
+                Constraint::Length(manifest_height),
+                Constraint::Min(1),
+            ])
             .split(inner);
 
-        let header_lines = vec![
+        let mut header_lines = vec![
             Line::from(vec![
                 Span::styled("Name: ", Style::default().add_modifier(Modifier::BOLD)),
                 Span::styled(&skill.name, Style::default().fg(Color::White)),
             ]),
             Line::from(vec![
                 Span::styled("Source: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::styled(&skill.source, Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    self.source_url.as_deref().unwrap_or(&skill.source),
+                    Style::default().fg(Color::Cyan),
+                ),
             ]),
             Line::from(vec![
                 Span::styled("Installs: ", Style::default().add_modifier(Modifier::BOLD)),
                 Span::raw(skill.installs.to_string()),
             ]),
+            Line::from(vec![
+                Span::styled("Ref: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    self.detail
+                        .and_then(|d| d.git_ref.as_deref())
+                        .unwrap_or("-")
+                        .to_string(),
+                    Style::default().fg(Color::Magenta),
+                ),
+            ]),
+        ];
+        for (label, value) in &self.fields {
+            header_lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{}: ", label),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(value.clone()),
+            ]));
+        }
+        header_lines.extend([
             Line::default(),
             Line::from(vec![
                 Span::styled("Install: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::styled(skill.install_command(), Style::default().fg(Color::Green)),
+                Span::styled(
+                    self.install_command
+                        .clone()
+                        .unwrap_or_else(|| "-".to_string()),
+                    Style::default().fg(Color::Green),
+                ),
             ]),
-        ];
+        ]);
 
         Paragraph::new(header_lines).render(chunks[0], buf);
 
+        if !manifest_rows.is_empty() {
+            let rows: Vec<Row> = manifest_rows.into_iter().map(|(row, _)| row).collect();
+            Table::new(
+                rows,
+                [Constraint::Length(MANIFEST_KEY_WIDTH), Constraint::Min(1)],
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::TOP | Borders::BOTTOM)
+                    .title(" Manifest ")
+                    .border_style(Style::default().fg(Color::DarkGray)),
+            )
+            .render(chunks[1], buf);
+        }
+
         if self.loading {
             let loading_msg =
                 Paragraph::new("⟳ Loading README...").style(Style::default().fg(Color::Yellow));
-            loading_msg.render(chunks[1], buf);
-        } else if let Some(md) = self.markdown {
-            let lines: Vec<Line> = md.lines().map(|l| Line::from(l.to_string())).collect();
-            Paragraph::new(lines)
-                .scroll((self.scroll, 0))
-                .render(chunks[1], buf);
+            loading_msg.render(chunks[2], buf);
+        } else if let Some(detail) = self.detail {
+            if self.raw {
+                let lines: Vec<Line> = detail
+                    .markdown
+                    .lines()
+                    .map(|l| Line::from(l.to_string()))
+                    .collect();
+                Paragraph::new(lines)
+                    .scroll((self.scroll, 0))
+                    .render(chunks[2], buf);
+            } else {
+                Paragraph::new(markdown::render(&detail.body))
+                    .wrap(Wrap { trim: false })
+                    .scroll((self.scroll, 0))
+                    .render(chunks[2], buf);
+            }
         } else {
             let hint =
                 Paragraph::new("No README available").style(Style::default().fg(Color::DarkGray));
-            hint.render(chunks[1], buf);
+            hint.render(chunks[2], buf);
+        }
+    }
+}
+
+fn manifest_rows(manifest: &SkillManifest, value_width: u16) -> Vec<(Row<'static>, u16)> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+    let scalars = [
+        ("Name", &manifest.name),
+        ("Description", &manifest.description),
+        ("License", &manifest.license),
+        ("Version", &manifest.version),
+    ];
+    for (key, value) in scalars {
+        if let Some(value) = value {
+            fields.push((key.to_string(), value.clone()));
+        }
+    }
+    if !manifest.allowed_tools.is_empty() {
+        fields.push((
+            "Allowed tools".to_string(),
+            manifest.allowed_tools.join(", "),
+        ));
+    }
+    for (key, value) in &manifest.metadata {
+        fields.push((key.clone(), value.clone()));
+    }
+
+    fields
+        .into_iter()
+        .map(|(key, value)| {
+            let lines = wrap_value(&value, value_width as usize);
+            let height = lines.len().max(1) as u16;
+            let row = Row::new(vec![
+                Text::from(key).style(Style::default().add_modifier(Modifier::BOLD)),
+                Text::from(lines.into_iter().map(Line::from).collect::<Vec<_>>()),
+            ])
+            .height(height);
+            (row, height)
+        })
+        .collect()
+}
+
+/// Word-wrap a manifest value to the table column, keeping at most
+/// `MAX_VALUE_LINES` lines so the body stays visible.
+fn wrap_value(value: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines: Vec<String> = Vec::new();
+
+    for paragraph in value.lines() {
+        let mut current = String::new();
+        for word in paragraph.split_whitespace() {
+            if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+        lines.push(current);
+    }
+
+    if lines.len() > MAX_VALUE_LINES {
+        lines.truncate(MAX_VALUE_LINES);
+        if let Some(last) = lines.last_mut() {
+            last.push('…');
         }
     }
+    lines
 }
@@ -36,13 +36,18 @@ impl Widget for HelpWidget {
             ("", ""),
             ("Tab", "Next view"),
             ("Shift+Tab", "Previous view"),
-            ("1/2/3", "Select view directly"),
+            ("1-9", "Select view directly"),
+            ("o", "Open a git repository"),
             ("", ""),
             ("Actions", ""),
             ("", ""),
             ("Enter", "View detail"),
+            ("m", "Toggle raw/rendered markdown"),
+            ("b", "Browse detail at branch/tag/commit"),
             ("/", "Search"),
             ("i", "Install selected skill"),
+            ("u", "Uninstall selected skill"),
+            ("U", "Update all outdated skills (Updates view)"),
             ("r", "Refresh"),
             ("", ""),
             ("General", ""),
@@ -1,19 +1,30 @@
+use crate::install::{InstallTarget, Removal};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Text},
+    text::{Line, Span, Text},
     widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
 };
 
 pub struct InstallModal<'a> {
+    title: &'a str,
     command: &'a str,
     output: &'a str,
 }
 
 impl<'a> InstallModal<'a> {
     pub fn new(command: &'a str, output: &'a str) -> Self {
-        Self { command, output }
+        Self {
+            title: " Installing Skill ",
+            command,
+            output,
+        }
+    }
+
+    pub fn title(mut self, title: &'a str) -> Self {
+        self.title = title;
+        self
     }
 }
 
@@ -30,7 +41,7 @@ impl<'a> Widget for InstallModal<'a> {
 
         // Main block with cyan border
         let block = Block::default()
-            .title(" Installing Skill ")
+            .title(self.title)
             .title_style(
                 Style::default()
                     .fg(Color::Cyan)
@@ -90,6 +101,171 @@ impl<'a> Widget for InstallModal<'a> {
     }
 }
 
+/// Asks where a skill should be installed.
+pub struct TargetPicker<'a> {
+    skill: &'a str,
+    targets: &'a [InstallTarget],
+    selected: usize,
+}
+
+impl<'a> TargetPicker<'a> {
+    pub fn new(skill: &'a str, targets: &'a [InstallTarget], selected: usize) -> Self {
+        Self {
+            skill,
+            targets,
+            selected,
+        }
+    }
+}
+
+impl<'a> Widget for TargetPicker<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let modal_width = (area.width as f32 * 0.9).min(80.0) as u16;
+        let modal_height = (self.targets.len() as u16 + 6).min(area.height);
+
+        let area = center_rect(area, modal_width, modal_height);
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title(format!(" Install {} ", self.skill))
+            .title_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let chunks = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Prompt
+                Constraint::Min(1),    // Targets
+                Constraint::Length(1), // Hint
+            ])
+            .margin(1)
+            .split(inner);
+
+        Paragraph::new(Line::from("Install into:").style(Style::default().fg(Color::Gray)))
+            .render(chunks[0], buf);
+
+        let agent_width = self
+            .targets
+            .iter()
+            .map(|t| t.agent.len())
+            .max()
+            .unwrap_or(0);
+        let lines: Vec<Line> = self
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(i, target)| {
+                let style = if i == self.selected {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!(
+                            " {:<agent_width$}  {:<7}  ",
+                            target.agent,
+                            target.scope.label(),
+                        ),
+                        style,
+                    ),
+                    Span::styled(
+                        format!("{} ", target.dir.display()),
+                        style.fg(if i == self.selected {
+                            Color::Black
+                        } else {
+                            Color::DarkGray
+                        }),
+                    ),
+                ])
+            })
+            .collect();
+        Paragraph::new(lines).render(chunks[1], buf);
+
+        let hint = Line::from("Enter to install, Esc to cancel")
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::DarkGray));
+        Paragraph::new(hint).render(chunks[2], buf);
+    }
+}
+
+/// Lists what uninstalling a skill moves to the trash and asks to go ahead.
+pub struct UninstallModal<'a> {
+    skill: &'a str,
+    removals: &'a [Removal],
+}
+
+impl<'a> UninstallModal<'a> {
+    pub fn new(skill: &'a str, removals: &'a [Removal]) -> Self {
+        Self { skill, removals }
+    }
+}
+
+impl<'a> Widget for UninstallModal<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let modal_width = (area.width as f32 * 0.9).min(80.0) as u16;
+        let modal_height = (area.height as f32 * 0.7).min(25.0) as u16;
+
+        let area = center_rect(area, modal_width, modal_height);
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title(format!(" Uninstall {} ", self.skill))
+            .title_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Red))
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let chunks = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Prompt
+                Constraint::Min(1),    // Folders and files
+                Constraint::Length(1), // Hint
+            ])
+            .margin(1)
+            .split(inner);
+
+        Paragraph::new(
+            Line::from("These folders move to the trash:").style(Style::default().fg(Color::Gray)),
+        )
+        .render(chunks[0], buf);
+
+        let mut lines = Vec::new();
+        for removal in self.removals {
+            for (i, line) in removal.to_string().lines().enumerate() {
+                let style = if i == 0 {
+                    Style::default().fg(Color::Yellow)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                lines.push(Line::from(line.to_string()).style(style));
+            }
+        }
+        Paragraph::new(lines).render(chunks[1], buf);
+
+        let hint = Line::from("Enter to uninstall, Esc to cancel")
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::DarkGray));
+        Paragraph::new(hint).render(chunks[2], buf);
+    }
+}
+
 fn center_rect(area: Rect, width: u16, height: u16) -> Rect {
     let horizontal = Layout::horizontal([Constraint::Length(width)]).flex(Flex::Center);
     let vertical = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center);
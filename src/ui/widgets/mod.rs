@@ -7,7 +7,7 @@ pub mod tabs;
 
 pub use detail::DetailWidget;
 pub use help::HelpWidget;
-pub use install_modal::InstallModal;
+pub use install_modal::{InstallModal, TargetPicker, UninstallModal};
 pub use skill_list::SkillListWidget;
 pub use status::StatusBar;
 pub use tabs::TabsWidget;
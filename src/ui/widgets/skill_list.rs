@@ -1,24 +1,41 @@
 use crate::app::state::ViewState;
-use crate::data::ViewKind;
+use crate::data::SkillRegistry;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Row, StatefulWidget, Table, TableState},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Row, StatefulWidget, Table, TableState},
 };
 
 pub struct SkillListWidget<'a> {
     view_state: &'a ViewState,
-    view_kind: ViewKind,
+    label: &'a str,
+    installed: Option<&'a dyn SkillRegistry>,
+    notes: Option<&'a dyn SkillRegistry>,
 }
 
 impl<'a> SkillListWidget<'a> {
-    pub fn new(view_state: &'a ViewState, view_kind: ViewKind) -> Self {
+    pub fn new(view_state: &'a ViewState, label: &'a str) -> Self {
         Self {
             view_state,
-            view_kind,
+            label,
+            installed: None,
+            notes: None,
         }
     }
+
+    /// Registry whose notes are shown after each skill's name.
+    pub fn notes(mut self, registry: &'a dyn SkillRegistry) -> Self {
+        self.notes = Some(registry);
+        self
+    }
+
+    /// Local registry whose scan marks skills as already installed.
+    pub fn installed(mut self, local: &'a dyn SkillRegistry) -> Self {
+        self.installed = Some(local);
+        self
+    }
 }
 
 impl<'a> StatefulWidget for SkillListWidget<'a> {
@@ -38,10 +55,27 @@ impl<'a> StatefulWidget for SkillListWidget<'a> {
         let rows: Vec<Row> = skills
             .iter()
             .map(|skill| {
+                let installed = self
+                    .installed
+                    .is_some_and(|local| local.has_installed(skill));
+                let mut name = vec![Span::raw(skill.name.clone())];
+                if installed {
+                    name.push(Span::styled(
+                        " ✓ installed",
+                        Style::default().fg(Color::Green),
+                    ));
+                }
+                if let Some(note) = self.notes.and_then(|registry| registry.list_note(skill)) {
+                    name.push(Span::styled(
+                        format!("  {}", note),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                let name = Line::from(name);
                 Row::new(vec![
-                    skill.name.clone(),
-                    skill.source.clone(),
-                    skill.installs.to_string(),
+                    Cell::from(name),
+                    Cell::from(skill.source.clone()),
+                    Cell::from(skill.installs.to_string()),
                 ])
                 .style(Style::default())
                 .height(1)
@@ -54,7 +88,12 @@ impl<'a> StatefulWidget for SkillListWidget<'a> {
             Constraint::Percentage(15),
         ];
 
-        let title = format!(" {} ({} skills) ", self.view_kind.label(), skills.len());
+        let mut title = format!(" {} ({} skills) ", self.label, skills.len());
+        if self.view_state.from_cache {
+            if let Some(age) = self.view_state.age() {
+                title.push_str(&format!("· cached {} min ago ", age.as_secs() / 60));
+            }
+        }
 
         let table = Table::new(rows, widths)
             .header(header)
@@ -1,4 +1,5 @@
 use crate::app::state::Mode;
+use crate::data::RateLimit;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -11,18 +12,25 @@ pub struct StatusBar {
     mode: Mode,
     message: Option<String>,
     loading: bool,
-    search_query: String,
+    input: String,
+    rate_limit: Option<RateLimit>,
 }
 
 impl StatusBar {
-    pub fn new(mode: Mode, message: Option<String>, loading: bool, search_query: String) -> Self {
+    pub fn new(mode: Mode, message: Option<String>, loading: bool, input: String) -> Self {
         Self {
             mode,
             message,
             loading,
-            search_query,
+            input,
+            rate_limit: None,
         }
     }
+
+    pub fn rate_limit(mut self, rate_limit: Option<RateLimit>) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
 }
 
 impl Widget for StatusBar {
@@ -43,7 +51,7 @@ impl Widget for StatusBar {
                     .add_modifier(Modifier::BOLD),
             ),
             Mode::Search => Span::styled(
-                format!(" SEARCH: {}█ ", self.search_query),
+                format!(" SEARCH: {}█ ", self.input),
                 Style::default()
                     .bg(Color::Yellow)
                     .fg(Color::Black)
@@ -56,6 +64,27 @@ impl Widget for StatusBar {
                     .fg(Color::White)
                     .add_modifier(Modifier::BOLD),
             ),
+            Mode::RefInput => Span::styled(
+                format!(" REF: {}█ ", self.input),
+                Style::default()
+                    .bg(Color::Green)
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Mode::RepoInput => Span::styled(
+                format!(" REPO: {}█ ", self.input),
+                Style::default()
+                    .bg(Color::Yellow)
+                    .fg(Color::Black)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Mode::PickTarget => Span::styled(
+                " TARGET ",
+                Style::default()
+                    .bg(Color::Yellow)
+                    .fg(Color::Black)
+                    .add_modifier(Modifier::BOLD),
+            ),
             Mode::Installing => Span::styled(
                 " INSTALL ",
                 Style::default()
@@ -63,6 +92,13 @@ impl Widget for StatusBar {
                     .fg(Color::Black)
                     .add_modifier(Modifier::BOLD),
             ),
+            Mode::ConfirmUninstall | Mode::Uninstalling => Span::styled(
+                " UNINSTALL ",
+                Style::default()
+                    .bg(Color::Red)
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ),
         };
 
         let loading_span = if self.loading {
@@ -76,16 +112,40 @@ impl Widget for StatusBar {
         });
 
         let hints = match self.mode {
-            Mode::List => " q:quit  /:search  i:install  r:refresh  ?:help  Tab:switch view ",
-            Mode::Detail => " Esc:back  j/k:navigate ",
+            Mode::List => {
+                " q:quit  /:search  i:install  u:uninstall  r:refresh  o:open repo  ?:help  Tab:switch view "
+            }
+            Mode::Detail => " Esc:back  j/k:navigate  m:raw/rendered  b:branch/tag ",
+            Mode::RefInput => " Esc:cancel  Enter:load (empty = default branch) ",
+            Mode::RepoInput => " Esc:cancel  Enter:open owner/repo or repository URL ",
             Mode::Search => " Esc:cancel  Enter:confirm ",
             Mode::Help => " Esc/?:close ",
-            Mode::Installing => " Enter/Esc:close ",
+            Mode::PickTarget => " j/k:choose  Enter:install  Esc:cancel ",
+            Mode::ConfirmUninstall => " Enter:move to trash  Esc:cancel ",
+            Mode::Installing | Mode::Uninstalling => " Enter/Esc:close ",
         };
 
         let hints_span = Span::styled(hints, Style::default().fg(Color::White));
 
-        let line = Line::from(vec![mode_span, loading_span, message_span, hints_span]);
+        let rate_limit_span = self.rate_limit.map_or(Span::raw(""), |limit| {
+            let color = if limit.remaining * 10 < limit.limit {
+                Color::Red
+            } else {
+                Color::DarkGray
+            };
+            Span::styled(
+                format!(" GitHub {}/{} ", limit.remaining, limit.limit),
+                Style::default().fg(color),
+            )
+        });
+
+        let line = Line::from(vec![
+            mode_span,
+            loading_span,
+            message_span,
+            hints_span,
+            rate_limit_span,
+        ]);
 
         Paragraph::new(line)
             .style(Style::default().bg(Color::Black))
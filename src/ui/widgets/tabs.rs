@@ -1,4 +1,4 @@
-use crate::data::ViewKind;
+use crate::app::state::AppState;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -7,36 +7,48 @@ use ratatui::{
     widgets::{Block, Borders, Tabs as RataTabs, Widget},
 };
 
-pub struct TabsWidget {
-    current: ViewKind,
+/// One tab per registry view. With several registries, the first tab of
+/// each registry's group is prefixed with the registry name.
+pub struct TabsWidget<'a> {
+    state: &'a AppState,
 }
 
-impl TabsWidget {
-    pub fn new(current: ViewKind) -> Self {
-        Self { current }
+impl<'a> TabsWidget<'a> {
+    pub fn new(state: &'a AppState) -> Self {
+        Self { state }
     }
 }
 
-impl Widget for TabsWidget {
+impl<'a> Widget for TabsWidget<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let titles: Vec<Line> = ViewKind::all()
+        let grouped = self.state.registries.len() > 1;
+        let titles: Vec<Line> = self
+            .state
+            .tabs
             .iter()
             .enumerate()
-            .map(|(i, view)| {
-                let num = format!("[{}] ", i + 1);
-                let label = view.label();
-                Line::from(vec![
-                    Span::styled(num, Style::default().fg(Color::DarkGray)),
-                    Span::raw(label),
-                ])
+            .map(|(i, tab)| {
+                let mut spans = Vec::new();
+                let starts_group = i == 0 || self.state.tabs[i - 1].registry != tab.registry;
+                if grouped && starts_group {
+                    let name = self.state.registries[tab.registry].name();
+                    spans.push(Span::styled(
+                        format!("{} › ", name),
+                        Style::default().fg(Color::Cyan),
+                    ));
+                }
+                if i < 9 {
+                    spans.push(Span::styled(
+                        format!("[{}] ", i + 1),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                spans.push(Span::raw(tab.view.label()));
+                Line::from(spans)
             })
             .collect();
 
-        let selected = match self.current {
-            ViewKind::AllTime => 0,
-            ViewKind::Trending => 1,
-            ViewKind::Hot => 2,
-        };
+        let selected = self.state.current_tab;
 
         let tabs = RataTabs::new(titles)
             .block(Block::default().borders(Borders::BOTTOM))
@@ -0,0 +1,78 @@
+use skillsm::data::cache::{git_blob_sha, DocumentEntry};
+use skillsm::data::{DocumentCache, SkillKey};
+use std::path::PathBuf;
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("skillsm-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn entry(content: &str) -> DocumentEntry {
+    DocumentEntry {
+        sha: git_blob_sha(content.as_bytes()),
+        path: "SKILL.md".to_string(),
+        git_ref: "main".to_string(),
+        etag: None,
+    }
+}
+
+#[test]
+fn hashes_documents_like_git() {
+    // `printf 'hello\n' | git hash-object --stdin`
+    assert_eq!(
+        git_blob_sha(b"hello\n"),
+        "ce013625030ba8dba906f756967f9e9ca394464a"
+    );
+}
+
+#[test]
+fn finds_documents_by_blob_sha() {
+    let dir = scratch("documents");
+    let cache = DocumentCache::open(dir.clone(), 1024);
+    let (pdf, copy) = (
+        SkillKey::new("acme/tools", "pdf"),
+        SkillKey::new("fork/tools", "pdf"),
+    );
+    cache.store(&pdf, entry("# PDF\n"), "# PDF\n").unwrap();
+    cache.store(&copy, entry("# PDF\n"), "# PDF\n").unwrap();
+
+    // Both keys share the one object, which a tree entry's sha finds too.
+    let sha = git_blob_sha(b"# PDF\n");
+    assert_eq!(cache.object(&sha).as_deref(), Some("# PDF\n"));
+    assert_eq!(std::fs::read_dir(dir.join("objects")).unwrap().count(), 1);
+    let (found, content) = cache.lookup(&copy).unwrap();
+    assert_eq!(
+        (found.sha.as_str(), content.as_str()),
+        (sha.as_str(), "# PDF\n")
+    );
+    assert!(cache.object(&git_blob_sha(b"# Other\n")).is_none());
+
+    // The index survives reopening the cache.
+    let reopened = DocumentCache::open(dir.clone(), 1024);
+    assert!(reopened.lookup(&pdf).is_some());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn evicts_least_recently_used_documents() {
+    let dir = scratch("eviction");
+    let cache = DocumentCache::open(dir.clone(), 25);
+    let key = |id: &str| SkillKey::new("acme/tools", id);
+    let (a, b, c) = ("# A 123456\n", "# B 123456\n", "# C 123456\n");
+
+    cache.store(&key("a"), entry(a), a).unwrap();
+    cache.store(&key("b"), entry(b), b).unwrap();
+    // Reading `a` makes `b` the least recently used.
+    assert!(cache.lookup(&key("a")).is_some());
+    cache.store(&key("c"), entry(c), c).unwrap();
+
+    assert!(cache.lookup(&key("a")).is_some());
+    assert!(cache.lookup(&key("b")).is_none());
+    assert!(cache.lookup(&key("c")).is_some());
+    assert!(!dir
+        .join("objects")
+        .join(format!("{}.md", git_blob_sha(b.as_bytes())))
+        .exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
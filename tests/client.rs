@@ -0,0 +1,318 @@
+use skillsm::data::host::{HostKind, HostSettings};
+use skillsm::data::{SkillKey, SkillsClient, ViewKind};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+
+const ALL_TIME: &str = include_str!("fixtures/skills_sh/synthetic/all-time.html");
+
+/// Serve the body `respond` returns for each request path on a local port,
+/// recording the request lines. Returns the base URL.
+fn stub_server(
+    respond: impl Fn(&str) -> String + Send + 'static,
+) -> (String, std::sync::mpsc::Receiver<String>) {
+    stub_server_with_status(move |path| (200, respond(path)))
+}
+
+/// [`stub_server`], answering with the status `respond` returns too.
+fn stub_server_with_status(
+    respond: impl Fn(&str) -> (u16, String) + Send + 'static,
+) -> (String, std::sync::mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let request_line = request_line.trim_end().to_string();
+            let path = request_line.split(' ').nth(1).unwrap_or_default();
+            let (status, body) = respond(path);
+            let _ = tx.send(request_line);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {} Stub\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+        }
+    });
+
+    (format!("http://{}", addr), rx)
+}
+
+#[tokio::test]
+async fn fetches_leaderboard_from_configured_registry() {
+    let (url, requests) = stub_server(|_| ALL_TIME.to_string());
+    let client = SkillsClient::builder()
+        .registry_url(format!("{}/", url))
+        .user_agent("skillsm-test")
+        .build()
+        .unwrap();
+
+    let leaderboard = client.fetch_skills(ViewKind::Trending).await.unwrap();
+    assert_eq!(leaderboard.skills.len(), 24);
+    assert_eq!(requests.recv().unwrap(), "GET /?view=trending HTTP/1.1");
+}
+
+#[test]
+fn rejects_invalid_proxy() {
+    let result = SkillsClient::builder().proxy("not a url").build();
+    assert!(matches!(result, Err(skillsm::error::AppError::Config(_))));
+}
+
+#[tokio::test]
+async fn fetches_skill_from_gitlab_host() {
+    let (url, requests) = stub_server(|path| {
+        let project = "/api/v4/projects/team%2Fagents%2Fskills";
+        match path.strip_prefix(project).unwrap_or(path) {
+            "" => r#"{"default_branch":"main"}"#.to_string(),
+            p if p.starts_with("/repository/tree?") && p.ends_with("page=1") => {
+                r#"[{"id":"0123","path":"pdf","type":"tree"},{"id":"4567","path":"pdf/SKILL.md","type":"blob"}]"#
+                    .to_string()
+            }
+            "/repository/files/pdf%2FSKILL.md/raw?ref=main" => {
+                "---\nname: pdf\ndescription: Work with PDFs\n---\n# PDF\n".to_string()
+            }
+            _ => "[]".to_string(),
+        }
+    });
+    let domain = url.trim_start_matches("http://").to_string();
+    let client = SkillsClient::builder()
+        .host(
+            &domain,
+            HostSettings {
+                kind: HostKind::Gitlab,
+                api_url: Some(format!("{}/api/v4", url)),
+                token: Some("secret".to_string()),
+                clone_url: None,
+            },
+        )
+        .build()
+        .unwrap();
+
+    let key = SkillKey::new(format!("{}/team/agents/skills", domain), "pdf");
+    let detail = client.fetch_detail(&key, None).await.unwrap();
+    assert_eq!(detail.git_ref.as_deref(), Some("main"));
+    assert_eq!(
+        detail.manifest.unwrap().description.as_deref(),
+        Some("Work with PDFs")
+    );
+
+    let requests: Vec<String> = requests.try_iter().collect();
+    assert_eq!(
+        requests[1],
+        "GET /api/v4/projects/team%2Fagents%2Fskills/repository/tree?recursive=true&ref=main&per_page=100&page=1 HTTP/1.1"
+    );
+}
+
+#[tokio::test]
+async fn searches_skill_documents_and_reports_truncated_trees() {
+    let (url, _requests) = stub_server(|path| match path {
+        "/repos/acme/skills" => r#"{"default_branch":"main"}"#.to_string(),
+        p if p.starts_with("/repos/acme/skills/git/trees/main") => {
+            let entries: Vec<String> = (0..20)
+                .map(|i| {
+                    format!(r#"{{"path":"skills/s{i}/SKILL.md","type":"blob","sha":"{i:040}"}}"#)
+                })
+                .collect();
+            format!(
+                r#"{{"sha":"main","truncated":true,"tree":[{}]}}"#,
+                entries.join(",")
+            )
+        }
+        p => {
+            let folder = p.trim_end_matches("/SKILL.md").rsplit('/').next().unwrap();
+            let name = if folder == "s13" { "renamed" } else { folder };
+            format!("---\nname: {}\n---\n# {}\n", name, folder)
+        }
+    });
+    let client = SkillsClient::builder()
+        .github_api_url(url.clone())
+        .github_raw_url(url)
+        .build()
+        .unwrap();
+
+    let renamed = client
+        .fetch_detail(&SkillKey::new("acme/skills", "renamed"), None)
+        .await
+        .unwrap();
+    assert!(renamed.markdown.contains("# s13"));
+
+    let missing = client
+        .fetch_detail(&SkillKey::new("acme/skills", "absent"), None)
+        .await;
+    assert!(matches!(
+        missing,
+        Err(skillsm::error::AppError::TreeTruncated(_))
+    ));
+}
+
+#[tokio::test]
+async fn revalidates_cached_documents_and_falls_back_when_offline() {
+    use skillsm::data::cache::{git_blob_sha, DocumentEntry};
+    use skillsm::data::DocumentCache;
+
+    let dir = std::env::temp_dir().join(format!("skillsm-fallback-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let cache = DocumentCache::open(dir.clone(), 1024);
+    let key = SkillKey::new("acme/skills", "pdf");
+    let content = "---\nname: pdf\n---\n# Cached\n";
+    let entry = DocumentEntry {
+        sha: git_blob_sha(content.as_bytes()),
+        path: "pdf/SKILL.md".to_string(),
+        git_ref: "main".to_string(),
+        etag: None,
+    };
+    cache.store(&key, entry, content).unwrap();
+
+    // Nothing listens on the port of a dropped listener.
+    let offline = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    };
+    let client = SkillsClient::builder()
+        .github_api_url(offline.clone())
+        .github_raw_url(offline)
+        .document_cache(cache.clone())
+        .build()
+        .unwrap();
+    let detail = client.fetch_detail(&key, None).await.unwrap();
+    assert!(detail.markdown.contains("# Cached"));
+
+    // Reachable, an unchanged document costs one conditional request.
+    let (url, requests) = stub_server_with_status(|_| (304, String::new()));
+    let client = SkillsClient::builder()
+        .github_api_url(url.clone())
+        .github_raw_url(url)
+        .document_cache(cache.clone())
+        .build()
+        .unwrap();
+    let detail = client.fetch_detail(&key, None).await.unwrap();
+    assert!(detail.markdown.contains("# Cached"));
+    let requests: Vec<String> = requests.try_iter().collect();
+    assert_eq!(requests, ["GET /acme/skills/main/pdf/SKILL.md HTTP/1.1"]);
+
+    // A repository that no longer has the skill is not papered over.
+    let (url, _requests) = stub_server_with_status(|path| match path {
+        "/repos/acme/skills" => (200, r#"{"default_branch":"main"}"#.to_string()),
+        "/acme/skills/main/pdf/SKILL.md" => (404, String::new()),
+        _ => (200, r#"{"sha":"main","tree":[]}"#.to_string()),
+    });
+    let client = SkillsClient::builder()
+        .github_api_url(url.clone())
+        .github_raw_url(url)
+        .document_cache(cache)
+        .build()
+        .unwrap();
+    let missing = client.fetch_detail(&key, None).await;
+    assert!(matches!(
+        missing,
+        Err(skillsm::error::AppError::NotFound(_))
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn checks_updates_by_commit_before_downloading() {
+    use skillsm::data::installed::UPDATES_VIEW;
+    use skillsm::data::{InstalledRegistry, SkillFile, SkillFiles, SkillRegistry};
+    use skillsm::install::{install, InstallTarget, Receipt, Scope};
+
+    let root = std::env::temp_dir().join(format!("skillsm-update-check-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let target = InstallTarget {
+        agent: "claude".to_string(),
+        scope: Scope::Global,
+        dir: root.join("skills"),
+    };
+    let files = SkillFiles {
+        git_ref: Some("main".to_string()),
+        commit: Some("c0ffee".to_string()),
+        files: vec![SkillFile {
+            path: "SKILL.md".to_string(),
+            contents: b"---\nname: pdf\n---\n".to_vec(),
+            executable: false,
+        }],
+    };
+    let receipt = Receipt {
+        registry: "skills-sh".to_string(),
+        source: "acme/skills".to_string(),
+        skill_id: "pdf".to_string(),
+        git_ref: files.git_ref.clone(),
+        commit: files.commit.clone(),
+        hash: None,
+        installed_at: 0,
+    };
+    install(&target.dir, "pdf", &files, &receipt, &root.join("trash")).unwrap();
+
+    let (url, requests) = stub_server(|_| r#"{"sha":"c0ffee"}"#.to_string());
+    let client = SkillsClient::builder()
+        .github_api_url(url.clone())
+        .github_raw_url(url)
+        .build()
+        .unwrap();
+    let registry = InstalledRegistry::new(vec![target]).upstream(Vec::new(), client);
+    let updates = registry.list(UPDATES_VIEW).await.unwrap();
+    assert!(updates.skills.is_empty(), "{:?}", updates.warnings);
+
+    let requests: Vec<String> = requests.try_iter().collect();
+    assert_eq!(
+        requests,
+        ["GET /repos/acme/skills/commits/main HTTP/1.1".to_string()]
+    );
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test]
+async fn lists_repository_skills_that_share_a_folder_name() {
+    use skillsm::data::{RepoReference, RepoRegistry, SkillRegistry};
+
+    let (url, _requests) = stub_server(|path| match path {
+        "/repos/acme/skills" => r#"{"default_branch":"main"}"#.to_string(),
+        p if p.starts_with("/repos/acme/skills/git/trees/main") => {
+            let entries: Vec<String> = ["a/pdf", "b/pdf", "notes"]
+                .iter()
+                .map(|dir| format!(r#"{{"path":"{dir}/SKILL.md","type":"blob","sha":"{dir}"}}"#))
+                .collect();
+            format!(r#"{{"sha":"main","tree":[{}]}}"#, entries.join(","))
+        }
+        "/acme/skills/main/a/pdf/SKILL.md" => "---\nname: pdf-forms\n---\n# A\n".to_string(),
+        "/acme/skills/main/b/pdf/SKILL.md" => "---\nname: pdf-tables\n---\n# B\n".to_string(),
+        _ => "# Notes\n".to_string(),
+    });
+    let client = SkillsClient::builder()
+        .github_api_url(url.clone())
+        .github_raw_url(url)
+        .build()
+        .unwrap();
+    let registry = RepoRegistry::new(RepoReference::parse("acme/skills").unwrap(), client);
+
+    let skills = registry.list(ViewKind::Catalog).await.unwrap().skills;
+    let rows: Vec<(&str, &str)> = skills
+        .iter()
+        .map(|s| (s.skill_id.as_str(), s.name.as_str()))
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("notes", "notes"),
+            ("a/pdf", "pdf-forms"),
+            ("b/pdf", "pdf-tables")
+        ]
+    );
+
+    let detail = registry.fetch_detail(&skills[2].key(), None).await.unwrap();
+    assert!(detail.markdown.contains("# B"));
+    assert_eq!(
+        skillsm::install::install_folder(&skills[2].skill_id).unwrap(),
+        "pdf"
+    );
+}
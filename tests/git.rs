@@ -0,0 +1,287 @@
+use skillsm::config::FetchMode;
+use skillsm::data::host::{HostKind, HostSettings};
+use skillsm::data::{SkillKey, SkillsClient};
+use std::path::Path;
+use std::process::Command;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// A repository at `<root>/remotes/team/skills` with one skill on `main`.
+fn remote_repo(root: &Path) {
+    let repo = root.join("remotes/team/skills");
+    std::fs::create_dir_all(repo.join("skills/pdf/scripts")).unwrap();
+    std::fs::write(
+        repo.join("skills/pdf/SKILL.md"),
+        "---\nname: pdf\ndescription: Work with PDFs\n---\n# PDF\n",
+    )
+    .unwrap();
+    std::fs::write(repo.join("skills/pdf/scripts/fill.py"), "print('fill')\n").unwrap();
+    std::fs::create_dir_all(repo.join("skills/pdf/forms")).unwrap();
+    std::fs::write(
+        repo.join("skills/pdf/forms/SKILL.md"),
+        "---\nname: pdf-forms\n---\n",
+    )
+    .unwrap();
+    git(&repo, &["init", "--quiet", "--initial-branch", "main"]);
+    git(&repo, &["add", "."]);
+    git(
+        &repo,
+        &[
+            "update-index",
+            "--add",
+            "--chmod=+x",
+            "skills/pdf/scripts/fill.py",
+        ],
+    );
+    git(&repo, &["commit", "--quiet", "-m", "Add pdf skill"]);
+}
+
+#[tokio::test]
+async fn reads_skills_from_a_git_checkout() {
+    let root = std::env::temp_dir().join(format!("skillsm-git-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    remote_repo(&root);
+
+    let client = SkillsClient::builder()
+        .fetch_mode(FetchMode::Git)
+        .git_checkouts(root.join("checkouts"))
+        .host(
+            "git.example.test",
+            HostSettings {
+                kind: HostKind::Gitlab,
+                api_url: None,
+                token: None,
+                clone_url: Some(format!("file://{}/remotes", root.display())),
+            },
+        )
+        .build()
+        .unwrap();
+
+    let key = SkillKey::new("git.example.test/team/skills", "pdf");
+    let detail = client.fetch_detail(&key, None).await.unwrap();
+    assert_eq!(detail.git_ref.as_deref(), Some("main"));
+    assert_eq!(
+        detail.manifest.unwrap().description.as_deref(),
+        Some("Work with PDFs")
+    );
+
+    // Sibling files are on disk next to the SKILL.md.
+    let checkout = client.checkout(&key.source, "main").await.unwrap();
+    assert!(checkout.join("skills/pdf/scripts/fill.py").is_file());
+
+    // The skill's folder, without the nested skill inside it.
+    let files = client.fetch_skill_files(&key, None).await.unwrap();
+    let paths: Vec<(&str, bool)> = files
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f.executable))
+        .collect();
+    assert_eq!(paths, [("SKILL.md", false), ("scripts/fill.py", true)]);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test]
+async fn flags_and_applies_upstream_changes() {
+    use skillsm::data::installed::UPDATES_VIEW;
+    use skillsm::data::{InstalledRegistry, SkillRegistry, SkillSummary};
+    use skillsm::install::{install, InstallTarget, Receipt, Scope};
+    use std::collections::BTreeMap;
+
+    let root = std::env::temp_dir().join(format!("skillsm-updates-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    remote_repo(&root);
+
+    // Each client fetches a ref once per session, so a fresh one sees new
+    // commits.
+    let client = |n: u32| {
+        SkillsClient::builder()
+            .fetch_mode(FetchMode::Git)
+            .git_checkouts(root.join(format!("checkouts-{}", n)))
+            .host(
+                "git.example.test",
+                HostSettings {
+                    kind: HostKind::Gitlab,
+                    api_url: None,
+                    token: None,
+                    clone_url: Some(format!("file://{}/remotes", root.display())),
+                },
+            )
+            .build()
+            .unwrap()
+    };
+    let target = InstallTarget {
+        agent: "claude".to_string(),
+        scope: Scope::Global,
+        dir: root.join("skills"),
+    };
+    let skill = SkillSummary {
+        source: "git.example.test/team/skills".to_string(),
+        skill_id: "pdf".to_string(),
+        name: "pdf".to_string(),
+        installs: 0,
+        installs_yesterday: None,
+        change: None,
+        extra: BTreeMap::new(),
+    };
+
+    let files = client(1)
+        .fetch_skill_files(&skill.key(), None)
+        .await
+        .unwrap();
+    let installed_commit = files.commit.clone().unwrap();
+    let receipt = Receipt::new("skills-sh", &skill, &files);
+    install(&target.dir, "pdf", &files, &receipt, &root.join("trash")).unwrap();
+
+    let registry = InstalledRegistry::new(vec![target.clone()]).upstream(Vec::new(), client(2));
+    let updates = registry.list(UPDATES_VIEW).await.unwrap();
+    assert!(updates.skills.is_empty(), "{:?}", updates.warnings);
+
+    let repo = root.join("remotes/team/skills");
+    std::fs::write(
+        repo.join("skills/pdf/SKILL.md"),
+        "---\nname: pdf\ndescription: Work with PDF forms\n---\n# PDF\n",
+    )
+    .unwrap();
+    git(&repo, &["commit", "--quiet", "-am", "Describe forms"]);
+
+    let registry = InstalledRegistry::new(vec![target.clone()]).upstream(Vec::new(), client(3));
+    let updates = registry.list(UPDATES_VIEW).await.unwrap();
+    assert_eq!(updates.skills.len(), 1, "{:?}", updates.warnings);
+    let update = registry.updates().remove(0);
+    let upstream_commit = update.upstream.commit.clone().unwrap();
+    assert_ne!(installed_commit, upstream_commit);
+    assert_eq!(
+        update.range(),
+        format!("{}..{}", &installed_commit[..7], &upstream_commit[..7])
+    );
+    assert!(registry
+        .list_note(&updates.skills[0])
+        .unwrap()
+        .ends_with(&update.range()));
+
+    let copy = &update.copies[0];
+    let receipt = copy.receipt.as_ref().unwrap().renewed(&update.upstream);
+    install(
+        &target.dir,
+        "pdf",
+        &update.upstream,
+        &receipt,
+        &root.join("trash"),
+    )
+    .unwrap();
+    let updates = registry.list(UPDATES_VIEW).await.unwrap();
+    assert!(updates.skills.is_empty());
+    let receipt = Receipt::read(&target.dir.join("pdf")).unwrap();
+    assert_eq!(receipt.commit, Some(upstream_commit));
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test]
+async fn refuses_refs_and_urls_that_look_like_options() {
+    use skillsm::data::git::GitCheckouts;
+    use skillsm::error::AppError;
+
+    let root = std::env::temp_dir().join(format!("skillsm-git-options-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    remote_repo(&root);
+    let url = format!("file://{}", root.join("remotes/team/skills").display());
+    let marker = root.join("pwned");
+    let checkouts = GitCheckouts::new(root.join("checkouts"));
+
+    let injected = format!("--upload-pack=touch {}", marker.display());
+    let result = checkouts.checkout(&url, "team/skills", &injected).await;
+    assert!(matches!(result, Err(AppError::Git(_))));
+    let result = checkouts.default_branch(&injected).await;
+    assert!(matches!(result, Err(AppError::Git(_))));
+    assert!(!marker.exists());
+
+    assert_eq!(checkouts.default_branch(&url).await.unwrap(), "main");
+    assert!(checkouts
+        .checkout(&url, "team/skills", "main")
+        .await
+        .is_ok());
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test]
+async fn leaves_symlinks_out_of_skill_files() {
+    use skillsm::data::tree::{SkillIndex, TreeResponse};
+
+    let root = std::env::temp_dir().join(format!("skillsm-git-symlink-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    remote_repo(&root);
+    let secret = root.join("secret.txt");
+    std::fs::write(&secret, "private key\n").unwrap();
+    let repo = root.join("remotes/team/skills");
+    std::os::unix::fs::symlink(&secret, repo.join("skills/pdf/notes.md")).unwrap();
+    git(&repo, &["add", "."]);
+    git(&repo, &["commit", "--quiet", "-m", "Link notes"]);
+
+    let client = SkillsClient::builder()
+        .fetch_mode(FetchMode::Git)
+        .git_checkouts(root.join("checkouts"))
+        .host(
+            "git.example.test",
+            HostSettings {
+                kind: HostKind::Gitlab,
+                api_url: None,
+                token: None,
+                clone_url: Some(format!("file://{}/remotes", root.display())),
+            },
+        )
+        .build()
+        .unwrap();
+    let key = SkillKey::new("git.example.test/team/skills", "pdf");
+    let files = client.fetch_skill_files(&key, None).await.unwrap();
+    let paths: Vec<&str> = files.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, ["SKILL.md", "scripts/fill.py"]);
+
+    // API listings mark symlinks by their mode.
+    let tree: TreeResponse = serde_json::from_str(
+        r#"{"sha":"main","tree":[
+            {"path":"pdf/SKILL.md","type":"blob","sha":"a","mode":"100644"},
+            {"path":"pdf/notes.md","type":"blob","sha":"b","mode":"120000"}]}"#,
+    )
+    .unwrap();
+    let index = SkillIndex::from_tree(tree, "skills");
+    assert_eq!(index.folder_files("pdf"), ["pdf/SKILL.md"]);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn installs_only_skill_folders_of_a_root_skill() {
+    use skillsm::data::tree::{SkillIndex, TreeResponse};
+
+    let tree: TreeResponse = serde_json::from_str(
+        r#"{"sha":"main","tree":[
+            {"path":"SKILL.md","type":"blob","sha":"a"},
+            {"path":"README.md","type":"blob","sha":"b"},
+            {"path":"src/main.rs","type":"blob","sha":"c"},
+            {"path":"scripts/run.sh","type":"blob","sha":"d","mode":"100755"},
+            {"path":"references/api.md","type":"blob","sha":"e"},
+            {"path":"assets/logo.png","type":"blob","sha":"f"},
+            {"path":"scripts-old/run.sh","type":"blob","sha":"g"}]}"#,
+    )
+    .unwrap();
+    let index = SkillIndex::from_tree(tree, "tool");
+    assert_eq!(
+        index.folder_files(""),
+        [
+            "SKILL.md",
+            "scripts/run.sh",
+            "references/api.md",
+            "assets/logo.png"
+        ]
+    );
+}
@@ -0,0 +1,46 @@
+use skillsm::data::index::INDEX_VERSION;
+use skillsm::data::{IndexRegistry, RegistryIndex, SkillRegistry, SkillsClient, ViewKind};
+use skillsm::error::AppError;
+
+#[tokio::test]
+async fn builds_and_browses_an_index() {
+    let root = std::env::temp_dir().join(format!("skillsm-index-{}", std::process::id()));
+    let skill_dir = root.join("skills/pdf");
+    std::fs::create_dir_all(&skill_dir).unwrap();
+    std::fs::write(
+        skill_dir.join("SKILL.md"),
+        "---\nname: PDF Tools\ndescription: Work with PDFs\n---\n# PDF\n",
+    )
+    .unwrap();
+
+    let index = RegistryIndex::build(&root.join("skills"), &root).unwrap();
+    assert_eq!(index.version, INDEX_VERSION);
+    assert_eq!(index.skills[0].content, "skills/pdf/SKILL.md");
+    assert_eq!(
+        index.skills[0].description.as_deref(),
+        Some("Work with PDFs")
+    );
+    let index_path = root.join("index.json");
+    std::fs::write(&index_path, index.to_json().unwrap()).unwrap();
+
+    let url = format!("file://{}", index_path.display());
+    assert_eq!(RegistryIndex::load(&url).unwrap(), index);
+
+    let registry = IndexRegistry::new("team", "team", url, SkillsClient::new().unwrap());
+    let skills = registry.list(ViewKind::Catalog).await.unwrap().skills;
+    assert_eq!(skills[0].name, "PDF Tools");
+    let detail = registry.fetch_detail(&skills[0].key(), None).await.unwrap();
+    assert_eq!(detail.body.trim(), "# PDF");
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn rejects_newer_index_versions() {
+    let json = format!(r#"{{"version": {}, "skills": []}}"#, INDEX_VERSION + 1);
+    assert!(matches!(
+        RegistryIndex::parse(&json),
+        Err(AppError::Parse(_))
+    ));
+    assert!(RegistryIndex::parse(r#"{"skills": []}"#).is_err());
+}
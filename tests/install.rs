@@ -0,0 +1,376 @@
+use skillsm::data::{SkillFile, SkillFiles};
+use skillsm::error::AppError;
+use skillsm::install::{folder_name, install, Receipt};
+
+fn file(path: &str, contents: &str) -> SkillFile {
+    SkillFile {
+        path: path.to_string(),
+        contents: contents.as_bytes().to_vec(),
+        executable: path.ends_with(".sh"),
+    }
+}
+
+fn receipt(skill_id: &str) -> Receipt {
+    Receipt {
+        registry: "skills-sh".to_string(),
+        source: "acme/skills".to_string(),
+        skill_id: skill_id.to_string(),
+        git_ref: Some("main".to_string()),
+        commit: None,
+        hash: None,
+        installed_at: 0,
+    }
+}
+
+fn files(files: Vec<SkillFile>) -> SkillFiles {
+    SkillFiles {
+        git_ref: Some("main".to_string()),
+        commit: None,
+        files,
+    }
+}
+
+#[test]
+fn installs_and_replaces_a_skill_folder() {
+    let root = std::env::temp_dir().join(format!("skillsm-install-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let (skills, trash) = (root.join("skills"), root.join("trash"));
+
+    let first = files(vec![
+        file("SKILL.md", "---\nname: pdf\n---\n"),
+        file("scripts/old.sh", "echo old\n"),
+    ]);
+    let replaced = install(&skills, "pdf", &first, &receipt("pdf"), &trash).unwrap();
+    assert_eq!(replaced, None);
+    let dest = skills.join("pdf");
+    assert!(dest.join("scripts/old.sh").is_file());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(dest.join("scripts/old.sh"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o111, 0o111);
+    }
+
+    // The replaced copy moves to the trash.
+    let second = files(vec![file("SKILL.md", "---\nname: pdf\n---\n# v2\n")]);
+    let replaced = install(&skills, "pdf", &second, &receipt("pdf"), &trash)
+        .unwrap()
+        .unwrap();
+    assert!(replaced.starts_with(&trash));
+    assert!(replaced.join("scripts/old.sh").is_file());
+    assert!(!dest.join("scripts").exists());
+    assert_eq!(
+        std::fs::read_to_string(dest.join("SKILL.md")).unwrap(),
+        "---\nname: pdf\n---\n# v2\n"
+    );
+
+    // A rejected install leaves the previous one and no staging folders.
+    let unsafe_files = files(vec![file("../escape.md", "")]);
+    let result = install(&skills, "pdf", &unsafe_files, &receipt("pdf"), &trash);
+    assert!(matches!(result, Err(AppError::InvalidSkill(_))));
+    assert!(dest.join("SKILL.md").is_file());
+    let entries: Vec<_> = std::fs::read_dir(&skills)
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .collect();
+    assert_eq!(entries, ["pdf"]);
+
+    // Neither another source's skill nor local edits are replaced.
+    let mut fork = receipt("pdf");
+    fork.source = "fork/skills".to_string();
+    let result = install(&skills, "pdf", &second, &fork, &trash);
+    assert!(matches!(result, Err(AppError::InvalidSkill(_))));
+    install(
+        &skills,
+        "pdf",
+        &second,
+        &receipt("pdf").renewed(&second),
+        &trash,
+    )
+    .unwrap();
+    std::fs::write(dest.join("notes.md"), "mine\n").unwrap();
+    let result = install(&skills, "pdf", &second, &receipt("pdf"), &trash);
+    assert!(matches!(result, Err(AppError::InvalidSkill(_))));
+    assert!(dest.join("notes.md").is_file());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn rejects_unsafe_folder_names() {
+    assert_eq!(folder_name("pdf").unwrap(), "pdf");
+    for id in ["", "..", ".hidden", "a/b", "a\\b"] {
+        assert!(folder_name(id).is_err(), "{:?} accepted", id);
+    }
+}
+
+#[test]
+fn skips_home_relative_targets_without_home() {
+    use skillsm::error::AppError;
+    use skillsm::install::{expand_home, targets, AgentDirs};
+    use std::path::{Path, PathBuf};
+
+    let agents = [AgentDirs {
+        name: "claude".to_string(),
+        global: Some(PathBuf::from("~/.claude/skills")),
+        project: Some(PathBuf::from(".claude/skills")),
+    }];
+    // No other test in this binary reads HOME.
+    let home = std::env::var_os("HOME");
+    std::env::remove_var("HOME");
+    let expanded = expand_home(Path::new("~/.claude/skills"));
+    let absolute = expand_home(Path::new("/opt/skills"));
+    let (found, warnings) = targets(&agents, Some(Path::new("/work/app")));
+    if let Some(home) = home {
+        std::env::set_var("HOME", home);
+    }
+
+    assert!(matches!(expanded, Err(AppError::Config(_))));
+    assert_eq!(absolute.unwrap(), Path::new("/opt/skills"));
+    let ids: Vec<String> = found.iter().map(|t| t.id()).collect();
+    assert_eq!(ids, ["claude:project"]);
+    assert_eq!(warnings.len(), 1);
+}
+
+#[test]
+fn lists_global_then_project_targets_once() {
+    use skillsm::install::{targets, AgentDirs, Scope, TargetMemory};
+    use std::path::{Path, PathBuf};
+
+    let agents = [
+        AgentDirs {
+            name: "claude".to_string(),
+            global: Some(PathBuf::from("/home/me/.claude/skills")),
+            project: Some(PathBuf::from(".claude/skills")),
+        },
+        AgentDirs {
+            name: "tool".to_string(),
+            global: None,
+            project: Some(PathBuf::from(".tool/skills")),
+        },
+    ];
+    let found: Vec<(String, Scope)> = targets(&agents, Some(Path::new("/work/app")))
+        .0
+        .into_iter()
+        .map(|t| (t.id(), t.scope))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("claude:global".to_string(), Scope::Global),
+            ("claude:project".to_string(), Scope::Project),
+            ("tool:project".to_string(), Scope::Project),
+        ]
+    );
+
+    // Run from the home directory, the project directory is the global one.
+    let (from_home, _) = targets(&agents[..1], Some(Path::new("/home/me")));
+    assert_eq!(from_home.len(), 1);
+    assert_eq!(from_home[0].scope, Scope::Global);
+
+    let path = std::env::temp_dir().join(format!("skillsm-targets-{}.json", std::process::id()));
+    let memory = TargetMemory::new(&path);
+    assert_eq!(memory.get(Path::new("/work/app")), None);
+    memory
+        .remember(Path::new("/work/app"), "claude:project")
+        .unwrap();
+    memory
+        .remember(Path::new("/work/other"), "tool:project")
+        .unwrap();
+    assert_eq!(
+        memory.get(Path::new("/work/app")).as_deref(),
+        Some("claude:project")
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn inventories_every_target() {
+    use skillsm::data::installed::INSTALLED_VIEW;
+    use skillsm::data::{InstalledRegistry, SkillKey, SkillRegistry, SkillSummary};
+    use skillsm::install::{InstallTarget, Scope};
+
+    let root = std::env::temp_dir().join(format!("skillsm-installed-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let target = |agent: &str, scope| InstallTarget {
+        agent: agent.to_string(),
+        scope,
+        dir: root.join(agent),
+    };
+    let claude = target("claude", Scope::Global);
+    let codex = target("codex", Scope::Project);
+
+    let pdf = files(vec![file(
+        "SKILL.md",
+        "---\nname: PDF Tools\ndescription: Work with PDFs\n---\n",
+    )]);
+    let trash = root.join("trash");
+    install(&claude.dir, "pdf", &pdf, &receipt("pdf"), &trash).unwrap();
+    install(&codex.dir, "pdf", &pdf, &receipt("pdf"), &trash).unwrap();
+    // Copied in by hand, so without a receipt.
+    std::fs::create_dir_all(codex.dir.join("notes")).unwrap();
+    std::fs::write(codex.dir.join("notes/SKILL.md"), "# Notes\n").unwrap();
+
+    let registry = InstalledRegistry::new(vec![
+        claude.clone(),
+        codex.clone(),
+        target("missing", Scope::Global),
+    ]);
+    let skills = registry.list(INSTALLED_VIEW).await.unwrap().skills;
+    let rows: Vec<(&str, &str, &str, i64)> = skills
+        .iter()
+        .map(|s| {
+            (
+                s.name.as_str(),
+                s.source.as_str(),
+                s.skill_id.as_str(),
+                s.installs,
+            )
+        })
+        .collect();
+    let codex_dir = codex.dir.display().to_string();
+    assert_eq!(
+        rows,
+        [
+            ("notes", codex_dir.as_str(), "notes", 1),
+            ("PDF Tools", "acme/skills", "pdf", 2),
+        ]
+    );
+
+    let key = SkillKey::new("acme/skills", "pdf");
+    let detail = registry.fetch_detail(&key, None).await.unwrap();
+    assert_eq!(detail.git_ref.as_deref(), Some("main"));
+    assert_eq!(
+        detail.manifest.unwrap().description.as_deref(),
+        Some("Work with PDFs")
+    );
+    let fields = registry.detail_fields(&skills[1]);
+    assert_eq!(
+        fields[0],
+        ("Origin", "acme/skills (from skills-sh)".to_string())
+    );
+    assert_eq!(
+        fields[2],
+        (
+            "Location",
+            format!("{} (codex:project)", codex.dir.join("pdf").display())
+        )
+    );
+
+    // Catalog rows show installs with a receipt by origin, and the others
+    // by folder or frontmatter name.
+    let row = |source: &str, skill_id: &str| SkillSummary {
+        source: source.to_string(),
+        skill_id: skill_id.to_string(),
+        name: skill_id.to_string(),
+        installs: 0,
+        installs_yesterday: None,
+        change: None,
+        extra: Default::default(),
+    };
+    assert!(registry.has_installed(&row("acme/skills", "pdf")));
+    assert!(!registry.has_installed(&row("fork/skills", "pdf")));
+    assert!(registry.has_installed(&row("someone/notes", "notes")));
+    // Only the row listing it picks a receipt-less copy for removal.
+    assert!(registry.copies(&SkillKey::new("x/y", "notes")).is_empty());
+    assert_eq!(registry.copies(&skills[0].key()).len(), 1);
+    std::fs::write(
+        codex.dir.join("notes/SKILL.md"),
+        "---\nname: meeting-notes\n---\n",
+    )
+    .unwrap();
+    registry.list(INSTALLED_VIEW).await.unwrap();
+    assert!(registry.has_installed(&row("someone/notes", "meeting-notes")));
+    assert!(!registry.has_installed(&row("someone/notes", "minutes")));
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn uninstall_moves_the_folder_to_the_trash() {
+    use skillsm::install::{uninstall, Removal};
+
+    let root = std::env::temp_dir().join(format!("skillsm-uninstall-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let skills = root.join("skills");
+    let trash = root.join("trash");
+
+    let pdf = files(vec![
+        file("SKILL.md", "---\nname: pdf\n---\n"),
+        file("scripts/fill.sh", "echo fill\n"),
+    ]);
+    install(&skills, "pdf", &pdf, &receipt("pdf"), &trash).unwrap();
+    let dir = skills.join("pdf");
+
+    let removal = Removal::new(&dir).unwrap();
+    assert_eq!(
+        removal.files,
+        [".skillsm.json", "SKILL.md", "scripts/fill.sh"]
+    );
+    assert_eq!(removal.link, None);
+
+    let first = uninstall(&dir, &trash).unwrap();
+    assert!(!dir.exists());
+    assert!(first.join("scripts/fill.sh").is_file());
+    assert!(first.starts_with(&trash));
+
+    // Uninstalling the same skill again in the same second keeps both.
+    install(&skills, "pdf", &pdf, &receipt("pdf"), &trash).unwrap();
+    let second = uninstall(&dir, &trash).unwrap();
+    assert_ne!(first, second);
+    assert!(first.join("SKILL.md").is_file() && second.join("SKILL.md").is_file());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn updates_move_the_old_copy_to_the_trash() {
+    use skillsm::install::{installed_in, update, InstallTarget, Scope};
+
+    let root = std::env::temp_dir().join(format!("skillsm-update-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let target = InstallTarget {
+        agent: "claude".to_string(),
+        scope: Scope::Global,
+        dir: root.join("skills"),
+    };
+    let trash = root.join("trash");
+
+    let v1 = files(vec![file("SKILL.md", "---\nname: pdf\n---\n# v1\n")]);
+    let v2 = files(vec![file("SKILL.md", "---\nname: pdf\n---\n# v2\n")]);
+    install(
+        &target.dir,
+        "pdf",
+        &v1,
+        &receipt("pdf").renewed(&v1),
+        &trash,
+    )
+    .unwrap();
+    let dir = target.dir.join("pdf");
+    let copy = installed_in(&target).unwrap().remove(0);
+    assert!(!copy.edited());
+
+    let old = update(&dir, &v2, &receipt("pdf").renewed(&v2), &trash).unwrap();
+    assert!(old.starts_with(&trash));
+    assert_eq!(
+        std::fs::read_to_string(old.join("SKILL.md")).unwrap(),
+        "---\nname: pdf\n---\n# v1\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("SKILL.md")).unwrap(),
+        "---\nname: pdf\n---\n# v2\n"
+    );
+
+    // Edits made after installing are noticed; receipts without a hash
+    // cannot tell.
+    std::fs::write(dir.join("notes.md"), "mine\n").unwrap();
+    assert!(installed_in(&target).unwrap()[0].edited());
+    update(&dir, &v2, &receipt("pdf"), &trash).unwrap();
+    std::fs::write(dir.join("notes.md"), "mine\n").unwrap();
+    assert!(!installed_in(&target).unwrap()[0].edited());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
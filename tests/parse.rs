@@ -0,0 +1,268 @@
+use skillsm::data::flight::decode_js_string;
+use skillsm::data::parse::extract_skills_from_html;
+use skillsm::error::AppError;
+
+// Hand-written pages in the shape of skills.sh's, seeded with edge cases a
+// live page rarely has: brackets, quotes and `</script>` in names. Pages
+// recorded from skills.sh itself live in `fixtures/skills_sh/recorded`.
+const ALL_TIME: &str = include_str!("fixtures/skills_sh/synthetic/all-time.html");
+const TRENDING: &str = include_str!("fixtures/skills_sh/synthetic/trending.html");
+const HOT: &str = include_str!("fixtures/skills_sh/synthetic/hot.html");
+
+/// Where [`parses_live_pages`] records skills.sh's pages.
+fn recorded_dir() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/skills_sh/recorded")
+}
+
+#[test]
+fn parses_recorded_pages() {
+    use skillsm::data::parse::extract_leaderboard;
+
+    let Ok(entries) = std::fs::read_dir(recorded_dir()) else {
+        return;
+    };
+    for entry in entries {
+        let path = entry.unwrap().path();
+        let html = std::fs::read_to_string(&path).unwrap();
+        let leaderboard = extract_leaderboard(&html).unwrap();
+        assert!(!leaderboard.skills.is_empty(), "{}", path.display());
+        assert!(
+            leaderboard.warnings.is_empty(),
+            "{}: {:?}",
+            path.display(),
+            leaderboard.warnings
+        );
+    }
+}
+
+/// Fetch each view from skills.sh and parse it, saving the pages into
+/// [`recorded_dir`] when `SKILLSM_RECORD_FIXTURES` is set.
+#[tokio::test]
+#[ignore = "fetches skills.sh; run with --ignored"]
+async fn parses_live_pages() {
+    use skillsm::data::parse::extract_leaderboard;
+    use skillsm::data::ViewKind;
+
+    let record = std::env::var_os("SKILLSM_RECORD_FIXTURES").is_some();
+    for view in [ViewKind::AllTime, ViewKind::Trending, ViewKind::Hot] {
+        let url = format!("https://skills.sh/?view={}", view.slug());
+        let html = reqwest::get(&url).await.unwrap().text().await.unwrap();
+        let leaderboard = extract_leaderboard(&html).unwrap();
+        assert!(!leaderboard.skills.is_empty(), "{}", url);
+        assert!(
+            leaderboard.warnings.is_empty(),
+            "{}: {:?}",
+            url,
+            leaderboard.warnings
+        );
+        if record {
+            std::fs::create_dir_all(recorded_dir()).unwrap();
+            let path = recorded_dir().join(format!("{}.html", view.slug()));
+            std::fs::write(path, html).unwrap();
+        }
+    }
+}
+
+#[test]
+fn extracts_every_synthetic_view() {
+    for html in [ALL_TIME, TRENDING, HOT] {
+        let skills = extract_skills_from_html(html).unwrap();
+        assert_eq!(skills.len(), 24);
+        assert!(skills
+            .iter()
+            .any(|s| s.source == "anthropics/skills" && s.skill_id == "pdf"));
+    }
+}
+
+#[test]
+fn keeps_view_specific_fields() {
+    let all_time = extract_skills_from_html(ALL_TIME).unwrap();
+    assert!(all_time.iter().all(|s| s.change.is_none()));
+
+    let trending = extract_skills_from_html(TRENDING).unwrap();
+    assert!(trending
+        .iter()
+        .all(|s| s.change.is_some() && s.installs_yesterday.is_some()));
+}
+
+#[test]
+fn decodes_names_with_brackets_quotes_and_escapes() {
+    let skills = extract_skills_from_html(HOT).unwrap();
+    let name_of = |id: &str| {
+        skills
+            .iter()
+            .find(|s| s.skill_id == id)
+            .map(|s| s.name.as_str())
+            .unwrap()
+    };
+
+    assert_eq!(
+        name_of("postgres-best-practices"),
+        "postgres [best] practices ]"
+    );
+    assert_eq!(
+        name_of("react-native-best-practices"),
+        "react-native \"best\" practices"
+    );
+    assert_eq!(name_of("better-auth"), "better-auth 🚀");
+    assert_eq!(name_of("workers"), "workers </script> edge");
+}
+
+#[test]
+fn reports_schema_change_without_flight_data() {
+    let err = extract_skills_from_html("<html><body>maintenance</body></html>").unwrap_err();
+    assert!(matches!(err, AppError::SchemaChanged(_)));
+}
+
+#[test]
+fn decodes_js_string_escapes() {
+    let (text, consumed) = decode_js_string(r#""a\"b\n\x41B\u{1F680}\ud83d\ude80" tail"#).unwrap();
+    assert_eq!(text, "a\"b\nAB🚀🚀");
+    assert_eq!(consumed, 34);
+}
+
+#[test]
+fn falls_back_to_leaderboard_markup() {
+    // Simulate a front-end refactor that drops the embedded flight data.
+    let html = ALL_TIME.replace("self.__next_f.push(", "self.__removed.push(");
+    let skills = extract_skills_from_html(&html).unwrap();
+
+    assert_eq!(skills.len(), 24);
+    let pdf = skills
+        .iter()
+        .find(|s| s.source == "anthropics/skills" && s.skill_id == "pdf")
+        .unwrap();
+    assert_eq!(pdf.name, "pdf");
+    assert!(pdf.installs > 0);
+}
+
+#[test]
+fn reports_drift_and_keeps_unknown_fields() {
+    use skillsm::data::parse::{detect_drift, extract_leaderboard};
+
+    assert!(extract_leaderboard(ALL_TIME).unwrap().warnings.is_empty());
+
+    let items: Vec<serde_json::Value> = serde_json::from_str(
+        r#"[{"source":"a/b","skillId":"x","name":"X","installs":1,"verified":true},
+            {"source":"a/b","skillId":"y","installs":2}]"#,
+    )
+    .unwrap();
+    let warnings = detect_drift(&items);
+    assert_eq!(
+        warnings,
+        [
+            "field `name` missing from 1 of 2 skills",
+            "new skill fields: `verified`"
+        ]
+    );
+
+    let html = r#"<script>self.__next_f.push([1,"0:[{\"source\":\"a/b\",\"skillId\":\"x\",\"name\":\"X\",\"installs\":1,\"verified\":true}]\n"])</script>"#;
+    let leaderboard = extract_leaderboard(html).unwrap();
+    assert_eq!(leaderboard.warnings, ["new skill fields: `verified`"]);
+    assert_eq!(
+        leaderboard.skills[0].extra.get("verified"),
+        Some(&serde_json::Value::Bool(true))
+    );
+}
+
+#[test]
+fn splits_frontmatter_from_body() {
+    use skillsm::data::parse::split_frontmatter;
+
+    let (frontmatter, body) = split_frontmatter("---\nname: pdf\n---\n\n# PDF\n");
+    assert_eq!(frontmatter, Some("name: pdf\n"));
+    assert_eq!(body, "# PDF\n");
+
+    let (frontmatter, body) = split_frontmatter("\u{feff}---\r\nname: pdf\r\n...\r\n# PDF\r\n");
+    assert_eq!(frontmatter, Some("name: pdf\r\n"));
+    assert_eq!(body, "# PDF\r\n");
+
+    for document in [
+        "# PDF\n---\nname: pdf\n---\n",
+        "---\nname: pdf\n# never closed\n",
+    ] {
+        assert_eq!(split_frontmatter(document), (None, document));
+    }
+}
+
+#[test]
+fn parses_scalars_with_quotes_and_comments() {
+    use skillsm::data::parse::parse_manifest;
+
+    let manifest = parse_manifest(
+        "name: \"pdf\" # the skill id\n\
+         description: 'It''s for PDFs' # quoted\n\
+         license: MIT # SPDX\n\
+         version: \"1.0 # not a comment\"\n",
+    );
+    assert_eq!(manifest.name.as_deref(), Some("pdf"));
+    assert_eq!(manifest.description.as_deref(), Some("It's for PDFs"));
+    assert_eq!(manifest.license.as_deref(), Some("MIT"));
+    assert_eq!(manifest.version.as_deref(), Some("1.0 # not a comment"));
+}
+
+#[test]
+fn parses_block_scalars_and_folded_lines() {
+    use skillsm::data::parse::parse_manifest;
+
+    let manifest = parse_manifest(
+        "name: pdf\n\
+         description: >\n  Extract text\n  from PDFs.\n\n  Fill forms.\n\
+         license: |\n  Apache-2.0\n  See LICENSE.txt\n\
+         version: a plain\n  folded value\n",
+    );
+    assert_eq!(
+        manifest.description.as_deref(),
+        Some("Extract text from PDFs.\nFill forms.")
+    );
+    assert_eq!(
+        manifest.license.as_deref(),
+        Some("Apache-2.0\nSee LICENSE.txt")
+    );
+    assert_eq!(manifest.version.as_deref(), Some("a plain folded value"));
+}
+
+#[test]
+fn parses_flow_and_block_lists() {
+    use skillsm::data::parse::parse_manifest;
+
+    let flow = parse_manifest("allowed-tools: [Read, \"Bash(git:*)\", 'Grep']\n");
+    assert_eq!(flow.allowed_tools, ["Read", "Bash(git:*)", "Grep"]);
+
+    let block = parse_manifest("allowed-tools:\n  - Read\n  - \"Write\" # files\n  -\n");
+    assert_eq!(block.allowed_tools, ["Read", "Write"]);
+
+    let scalar = parse_manifest("allowed_tools: Read Grep\ntags:\n  - pdf\n  - docs\n");
+    assert_eq!(scalar.allowed_tools, ["Read", "Grep"]);
+    assert_eq!(
+        scalar.metadata.get("tags").map(String::as_str),
+        Some("pdf, docs")
+    );
+}
+
+#[test]
+fn parses_nested_metadata_and_crlf() {
+    use skillsm::data::parse::{parse_manifest, split_frontmatter};
+
+    let document = "---\r\n\
+                    name: pdf\r\n\
+                    metadata:\r\n  author: \"Anthropic\"\r\n  notes: >\r\n    two\r\n    lines\r\n\
+                    homepage: https://example.com/#pdf # docs\r\n\
+                    ---\r\n";
+    let (frontmatter, _) = split_frontmatter(document);
+    let manifest = parse_manifest(frontmatter.unwrap());
+    assert_eq!(manifest.name.as_deref(), Some("pdf"));
+    let metadata: Vec<(&str, &str)> = manifest
+        .metadata
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    assert_eq!(
+        metadata,
+        [
+            ("author", "Anthropic"),
+            ("homepage", "https://example.com/#pdf"),
+            ("notes", "two lines"),
+        ]
+    );
+}
@@ -0,0 +1,92 @@
+use skillsm::data::plugin::{Marketplace, MarketplaceManifest, PluginManifest, PluginSkill};
+use skillsm::data::tree::{SkillIndex, TreeResponse};
+use std::collections::HashMap;
+
+fn index(paths: &[&str]) -> SkillIndex {
+    let tree: Vec<serde_json::Value> = paths
+        .iter()
+        .map(|path| serde_json::json!({"path": path, "type": "blob", "sha": "0"}))
+        .collect();
+    let tree: TreeResponse =
+        serde_json::from_value(serde_json::json!({"sha": "0", "tree": tree})).unwrap();
+    SkillIndex::from_tree(tree, "repo")
+}
+
+fn skill(folder: &str, path: &str) -> PluginSkill {
+    PluginSkill {
+        folder: folder.to_string(),
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn resolves_declared_and_default_skill_folders() {
+    let index = index(&[
+        "document-skills/pdf/SKILL.md",
+        "document-skills/xlsx/SKILL.md",
+        "plugins/review/skills/lint/SKILL.md",
+        "plugins/review/skills/style/SKILL.md",
+        "plugins/reviewer/skills/other/SKILL.md",
+        "unrelated/SKILL.md",
+    ]);
+    let manifest: MarketplaceManifest = serde_json::from_str(
+        r#"{
+            "name": "team-plugins",
+            "owner": {"name": "Team", "email": "team@example.com"},
+            "plugins": [
+                {"name": "documents", "source": "./", "skills": ["./document-skills/pdf"],
+                 "description": "Document skills"},
+                {"name": "review", "source": "./plugins/review"},
+                {"name": "external", "source": {"source": "github", "repo": "acme/plugin"}}
+            ]
+        }"#,
+    )
+    .unwrap();
+    let review: PluginManifest =
+        serde_json::from_str(r#"{"name": "review", "version": "1.2.0", "author": "Ada"}"#).unwrap();
+    let manifests = HashMap::from([("plugins/review".to_string(), review)]);
+
+    let marketplace = Marketplace::resolve(manifest, &manifests, &index);
+    assert_eq!(
+        marketplace.owner.as_deref(),
+        Some("Team <team@example.com>")
+    );
+
+    let [documents, review, external] = &marketplace.plugins[..] else {
+        panic!("expected three plugins");
+    };
+    assert_eq!(
+        documents.skills,
+        [skill("pdf", "document-skills/pdf/SKILL.md")]
+    );
+    assert_eq!(
+        review.skills,
+        [
+            skill("lint", "plugins/review/skills/lint/SKILL.md"),
+            skill("style", "plugins/review/skills/style/SKILL.md"),
+        ]
+    );
+    assert_eq!(review.manifest.version.as_deref(), Some("1.2.0"));
+    assert!(external.skills.is_empty());
+    assert_eq!(external.remote.as_deref(), Some("acme/plugin"));
+
+    let detail = review.detail(&marketplace, "acme/repo");
+    let manifest = detail.manifest.unwrap();
+    assert_eq!(manifest.version.as_deref(), Some("1.2.0"));
+    assert_eq!(manifest.metadata["author"], "Ada");
+    assert!(detail.body.contains("/plugin install review@team-plugins"));
+}
+
+#[test]
+fn treats_a_root_plugin_manifest_as_a_single_plugin() {
+    let index = index(&["skills/one/SKILL.md", "docs/SKILL.md"]);
+    let manifest: PluginManifest = serde_json::from_str(r#"{"description": "Solo"}"#).unwrap();
+
+    let marketplace = Marketplace::single("repo", manifest, &index);
+    assert_eq!(marketplace.plugins.len(), 1);
+    assert_eq!(marketplace.plugins[0].name, "repo");
+    assert_eq!(
+        marketplace.plugins[0].skills,
+        [skill("one", "skills/one/SKILL.md")]
+    );
+}
@@ -0,0 +1,403 @@
+use async_trait::async_trait;
+use skillsm::app::state::{AppState, TabId};
+use skillsm::data::{Leaderboard, SkillDetail, SkillKey, SkillRegistry, SkillSummary, ViewKind};
+use skillsm::error::{AppError, Result};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+struct Fixed {
+    name: &'static str,
+    views: Vec<ViewKind>,
+    skills: Vec<SkillSummary>,
+}
+
+fn skill(source: &str, skill_id: &str, name: &str) -> SkillSummary {
+    SkillSummary {
+        source: source.to_string(),
+        skill_id: skill_id.to_string(),
+        name: name.to_string(),
+        installs: 0,
+        installs_yesterday: None,
+        change: None,
+        extra: BTreeMap::new(),
+    }
+}
+
+#[async_trait]
+impl SkillRegistry for Fixed {
+    fn id(&self) -> &str {
+        self.name
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn views(&self) -> Vec<ViewKind> {
+        self.views.clone()
+    }
+
+    async fn list(&self, _view: ViewKind) -> Result<Leaderboard> {
+        Ok(Leaderboard {
+            skills: self.skills.clone(),
+            warnings: Vec::new(),
+        })
+    }
+
+    async fn fetch_detail(&self, key: &SkillKey, _git_ref: Option<&str>) -> Result<SkillDetail> {
+        Err(AppError::NotFound(key.to_string()))
+    }
+
+    fn install_command(&self, _skill: &SkillSummary) -> Option<Vec<String>> {
+        None
+    }
+}
+
+/// A local registry that has installed the skills it is given.
+struct Machine(Vec<SkillKey>);
+
+#[async_trait]
+impl SkillRegistry for Machine {
+    fn id(&self) -> &str {
+        "machine"
+    }
+
+    fn name(&self) -> &str {
+        "Machine"
+    }
+
+    fn views(&self) -> Vec<ViewKind> {
+        vec![ViewKind::Catalog]
+    }
+
+    async fn list(&self, _view: ViewKind) -> Result<Leaderboard> {
+        Ok(Leaderboard::default())
+    }
+
+    async fn fetch_detail(&self, key: &SkillKey, _git_ref: Option<&str>) -> Result<SkillDetail> {
+        Err(AppError::NotFound(key.to_string()))
+    }
+
+    fn install_command(&self, _skill: &SkillSummary) -> Option<Vec<String>> {
+        None
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    fn has_installed(&self, skill: &SkillSummary) -> bool {
+        self.0.contains(&skill.key())
+    }
+}
+
+#[test]
+fn groups_tabs_by_registry() {
+    let public = Fixed {
+        name: "public",
+        views: ViewKind::all().to_vec(),
+        skills: Vec::new(),
+    };
+    let internal = Fixed {
+        name: "internal",
+        views: vec![ViewKind::AllTime],
+        skills: Vec::new(),
+    };
+    let state = AppState::new(vec![Arc::new(public), Arc::new(internal)]);
+
+    assert_eq!(state.tabs.len(), 4);
+    assert_eq!(
+        state.tabs[3],
+        TabId {
+            registry: 1,
+            view: ViewKind::AllTime
+        }
+    );
+    assert_eq!(state.tab_label(state.tabs[3]), "internal · All Time");
+}
+
+#[tokio::test]
+async fn local_registry_scans_skill_folders() {
+    use skillsm::data::LocalRegistry;
+
+    let root = std::env::temp_dir().join(format!("skillsm-local-{}", std::process::id()));
+    let write = |rel: &str, content: &str| {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    };
+    write(
+        "team/pdf/SKILL.md",
+        "---\nname: PDF Tools\ndescription: Work with PDFs\n---\n# PDF\n",
+    );
+    write("writer/SKILL.md", "# Writer\n");
+    write(".git/hooks/SKILL.md", "ignored");
+    write("node_modules/pkg/SKILL.md", "ignored");
+
+    let registry = LocalRegistry::new("local", "local", &root);
+    let skills = registry.list(ViewKind::Catalog).await.unwrap().skills;
+    let found: Vec<(&str, &str, &str)> = skills
+        .iter()
+        .map(|s| (s.source.as_str(), s.skill_id.as_str(), s.name.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("team/pdf", "pdf", "PDF Tools"),
+            ("writer", "writer", "writer")
+        ]
+    );
+
+    let detail = registry.fetch_detail(&skills[0].key(), None).await.unwrap();
+    let manifest = detail.manifest.unwrap();
+    assert_eq!(manifest.description.as_deref(), Some("Work with PDFs"));
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn parses_repository_references() {
+    use skillsm::data::RepoReference;
+
+    let parse = |input: &str| RepoReference::parse(input).map(|r| (r.source, r.git_ref));
+    let repo =
+        |git_ref: Option<&str>| Some(("anthropics/skills".to_string(), git_ref.map(String::from)));
+
+    assert_eq!(parse("anthropics/skills"), repo(None));
+    assert_eq!(parse(" github.com/anthropics/skills/ "), repo(None));
+    assert_eq!(
+        parse("https://github.com/anthropics/skills.git"),
+        repo(None)
+    );
+    assert_eq!(
+        parse("https://github.com/anthropics/skills/tree/v2/document-skills"),
+        repo(Some("v2"))
+    );
+    assert_eq!(
+        parse("anthropics/skills@release/1.2"),
+        repo(Some("release/1.2"))
+    );
+    assert_eq!(
+        parse("https://github.com/anthropics/skills.git@release/1.2"),
+        repo(Some("release/1.2"))
+    );
+    assert_eq!(parse("anthropics/skills@"), None);
+    assert_eq!(parse("anthropics/skills/tree/v2@release/1.2"), None);
+    assert_eq!(parse("anthropics"), None);
+    assert_eq!(parse("anthropics/skills/issues"), None);
+    assert_eq!(parse("ftp://example.com/anthropics/skills"), None);
+
+    let other =
+        |source: &str, git_ref: Option<&str>| Some((source.to_string(), git_ref.map(String::from)));
+    assert_eq!(
+        parse("https://gitlab.com/group/sub/skills/-/tree/dev"),
+        other("gitlab.com/group/sub/skills", Some("dev"))
+    );
+    assert_eq!(
+        parse("codeberg.org/someone/skills.git"),
+        other("codeberg.org/someone/skills", None)
+    );
+    assert_eq!(
+        parse("https://codeberg.org/someone/skills/src/branch/main"),
+        other("codeberg.org/someone/skills", Some("main"))
+    );
+}
+
+#[test]
+fn install_commands_target_the_source_host() {
+    use skillsm::data::{SkillsClient, SkillsShRegistry};
+
+    let registry = SkillsShRegistry::new("skills-sh", "skills.sh", SkillsClient::new().unwrap());
+    let url = |source: &str| {
+        let command = registry
+            .install_command(&skill(source, "pdf", "pdf"))
+            .unwrap();
+        (
+            command[3].clone(),
+            registry.source_url(&skill(source, "pdf", "pdf")),
+        )
+    };
+
+    assert_eq!(
+        url("anthropics/skills"),
+        (
+            "https://github.com/anthropics/skills".to_string(),
+            Some("https://github.com/anthropics/skills".to_string())
+        )
+    );
+    assert_eq!(
+        url("gitlab.com/group/skills").0,
+        "https://gitlab.com/group/skills"
+    );
+    assert_eq!(
+        url("codeberg.org/someone/skills").0,
+        "https://codeberg.org/someone/skills.git"
+    );
+}
+
+#[test]
+fn install_asks_for_a_target() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use skillsm::app::events::{Action, Event};
+    use skillsm::app::state::Mode;
+    use skillsm::app::App;
+    use skillsm::install::{targets, AgentDirs, Installer};
+    use std::path::{Path, PathBuf};
+
+    let registry = Fixed {
+        name: "fixed",
+        views: vec![ViewKind::AllTime],
+        skills: Vec::new(),
+    };
+    let mut app = App::new(vec![Arc::new(registry)]);
+    app.state.installer = Installer::Native {
+        targets: targets(
+            &[AgentDirs {
+                name: "claude".to_string(),
+                global: Some(PathBuf::from("/home/me/.claude/skills")),
+                project: Some(PathBuf::from(".claude/skills")),
+            }],
+            Some(Path::new("/work/app")),
+        )
+        .0,
+    };
+    app.update(Event::ViewLoaded {
+        tab: app.state.tabs[0],
+        skills: vec![skill("acme/tools", "pdf", "PDF Tools")],
+        fetched_at: std::time::SystemTime::now(),
+        from_cache: false,
+    });
+
+    let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+    assert!(app.update(key(KeyCode::Char('i'))).is_empty());
+    assert_eq!(app.state.mode, Mode::PickTarget);
+    app.update(key(KeyCode::Down));
+    let actions = app.update(key(KeyCode::Enter));
+
+    let Some(Action::Install {
+        skill,
+        target: Some(target),
+        ..
+    }) = actions.first()
+    else {
+        panic!("expected an install, got {:?}", actions);
+    };
+    assert_eq!(skill.skill_id, "pdf");
+    assert_eq!(target.id(), "claude:project");
+    assert_eq!(target.dir, Path::new("/work/app/.claude/skills"));
+    assert_eq!(app.state.preferred_target, 1);
+    assert_eq!(app.state.mode, Mode::Installing);
+}
+
+#[test]
+fn uninstall_confirms_before_removing() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use skillsm::app::events::{Action, Event};
+    use skillsm::app::state::Mode;
+    use skillsm::app::App;
+    use skillsm::install::Removal;
+    use std::path::PathBuf;
+
+    let registry = Fixed {
+        name: "fixed",
+        views: vec![ViewKind::AllTime],
+        skills: Vec::new(),
+    };
+    let machine = Machine(vec![SkillKey::new("acme/tools", "pdf")]);
+    let mut app = App::new(vec![Arc::new(registry), Arc::new(machine)]);
+    app.update(Event::ViewLoaded {
+        tab: app.state.tabs[0],
+        skills: vec![
+            skill("acme/tools", "pdf", "PDF Tools"),
+            skill("acme/docs", "writer", "Writer"),
+        ],
+        fetched_at: std::time::SystemTime::now(),
+        from_cache: false,
+    });
+    let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    let actions = app.update(key(KeyCode::Char('u')));
+    assert!(matches!(
+        actions.as_slice(),
+        [Action::PlanUninstall { key, .. }] if key.skill_id == "pdf"
+    ));
+
+    // Skills that are not installed have nothing to remove.
+    app.update(key(KeyCode::Down));
+    assert!(app.update(key(KeyCode::Char('u'))).is_empty());
+    assert_eq!(
+        app.state.status_message.as_deref(),
+        Some("Writer is not installed")
+    );
+
+    let removal = Removal {
+        dir: PathBuf::from("/home/me/.claude/skills/pdf"),
+        link: None,
+        files: vec!["SKILL.md".to_string()],
+    };
+    app.update(Event::UninstallPlanned {
+        name: "PDF Tools".to_string(),
+        removals: vec![removal.clone()],
+    });
+    assert_eq!(app.state.mode, Mode::ConfirmUninstall);
+    let actions = app.update(key(KeyCode::Enter));
+    assert!(matches!(actions.as_slice(), [Action::Uninstall(r)] if *r == [removal.clone()]));
+    assert_eq!(app.state.mode, Mode::Uninstalling);
+}
+
+#[test]
+fn errors_land_on_the_tab_that_failed() {
+    use skillsm::app::events::{Event, Request};
+    use skillsm::app::App;
+    use skillsm::error::AppError;
+
+    let registry = Fixed {
+        name: "fixed",
+        views: vec![ViewKind::AllTime, ViewKind::Trending],
+        skills: Vec::new(),
+    };
+    let mut app = App::new(vec![Arc::new(registry)]);
+    let (current, background) = (app.state.tabs[0], app.state.tabs[1]);
+    for tab in [current, background] {
+        app.state.views.get_mut(&tab).unwrap().loading = true;
+    }
+
+    app.update(Event::error(
+        Request::View(background),
+        &AppError::NotFound("acme/tools".to_string()),
+    ));
+    assert_eq!(app.state.current_tab(), current);
+    assert!(app.state.views[&current].loading);
+    assert!(app.state.views[&current].error.is_none());
+    assert!(!app.state.views[&background].loading);
+    assert_eq!(
+        app.state.views[&background].error.as_deref(),
+        Some("Not found: acme/tools")
+    );
+}
+
+#[test]
+fn raw_urls_encode_each_path_segment() {
+    use skillsm::data::Hosts;
+
+    let hosts = Hosts::new("https://api.github.com", None);
+    let raw = |source: &str| {
+        hosts
+            .resolve(source)
+            .raw_url(
+                "https://raw.githubusercontent.com",
+                "feature/a b",
+                "skills/c#d/e?f.md",
+            )
+            .unwrap()
+    };
+
+    assert_eq!(
+        raw("acme/skills"),
+        "https://raw.githubusercontent.com/acme/skills/feature/a%20b/skills/c%23d/e%3Ff.md"
+    );
+    assert_eq!(
+        raw("codeberg.org/acme/skills"),
+        "https://codeberg.org/api/v1/repos/acme/skills/raw/skills/c%23d/e%3Ff.md?ref=feature%2Fa%20b"
+    );
+}